@@ -48,7 +48,6 @@
 //! [examples](https://github.com/orhanbalci/rough-rs/blob/main/points_on_curve/examples) folder.
 
 use std::borrow::Borrow;
-use std::cmp::{max_by, min_by};
 use std::fmt::Display;
 use std::ops::MulAssign;
 
@@ -67,6 +66,7 @@ use num_traits::Float;
 /// let result = distance_to_segment_squared(point2(0.0, 1.0), point2(-1.0, 0.0), point2(1.0, 0.0));
 /// assert_eq!(expected, result);
 /// ```
+/// Returns infinity when any of the points is not finite.
 pub fn distance_to_segment_squared<F, P>(p: P, v: P, w: P) -> F
 where
     F: Float + PartialOrd + Display,
@@ -75,27 +75,22 @@ where
     let v_ = v.borrow();
     let w_ = w.borrow();
     let p_ = p.borrow();
+    if !all_finite(&[*p_, *v_, *w_]) {
+        return F::infinity();
+    }
     let l2 = v_.distance_to(*w_).powi(2);
     if l2 == F::zero() {
         p_.distance_to(*v_).powi(2)
     } else {
         let mut t = ((p_.x - v_.x) * (w_.x - v_.x) + (p_.y - v_.y) * (w_.y - v_.y)) / l2;
-        t = max_by(
-            F::zero(),
-            min_by(F::one(), t, |a, b| {
-                a.partial_cmp(b)
-                    .unwrap_or_else(|| panic!("can not compare {} and {}", a, b))
-            }),
-            |a, b| {
-                a.partial_cmp(b)
-                    .unwrap_or_else(|| panic!("can not compare {} and {}", a, b))
-            },
-        );
+        // Float::max and min ignore a NaN operand instead of failing to compare it
+        t = t.min(F::one()).max(F::zero());
         p_.distance_to(v_.lerp(*w_, t)).powi(2)
     }
 }
 
 /// Adapted from https://seant23.wordpress.com/2010/11/12/offset-bezier-curves/
+/// Returns infinity when any of the curve points is not finite.
 pub fn flatness<F>(points: &[Point2D<F>], offset: usize) -> F
 where
     F: Float + MulAssign,
@@ -104,6 +99,9 @@ where
     let p2 = points[offset + 1];
     let p3 = points[offset + 2];
     let p4 = points[offset + 3];
+    if !all_finite(&[p1, p2, p3, p4]) {
+        return F::infinity();
+    }
 
     let const_3 = F::from(3).unwrap();
     let const_2 = F::from(2).unwrap();
@@ -169,6 +167,34 @@ where
     simplify_points(points, 0, points.len(), distance, &mut vec![])
 }
 
+/// Same as [`simplify`] but always keeps vertices where the polyline turns by more than
+/// `corner_angle_threshold` degrees, so intended sharp corners are not rounded off.
+pub fn simplify_preserve_corners<F>(
+    points: &[Point2D<F>],
+    distance: F,
+    corner_angle_threshold: F,
+) -> Vec<Point2D<F>>
+where
+    F: Float + Display,
+{
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+    let threshold = corner_angle_threshold.to_radians();
+    let mut new_points = vec![];
+    let mut start = 0;
+    for i in 1..points.len() - 1 {
+        let incoming = points[i] - points[i - 1];
+        let outgoing = points[i + 1] - points[i];
+        let turn = incoming.cross(outgoing).atan2(incoming.dot(outgoing)).abs();
+        if turn > threshold {
+            simplify_points(points, start, i + 1, distance, &mut new_points);
+            start = i;
+        }
+    }
+    simplify_points(points, start, points.len(), distance, &mut new_points)
+}
+
 fn get_points_on_bezier_curve_with_splitting<F>(
     points: &[Point2D<F>],
     offset: usize,
@@ -212,8 +238,13 @@ where
     new_points.to_vec()
 }
 
+fn all_finite<F: Float>(points: &[Point2D<F>]) -> bool {
+    points.iter().all(|p| p.x.is_finite() && p.y.is_finite())
+}
+
 /// Samples points on a Bezier Curve. If distance parameter is given does simplification on sampled points
-/// and reduces number of points that represents given Bezier Curve.
+/// and reduces number of points that represents given Bezier Curve. Curve segments with non finite points
+/// are skipped.
 pub fn points_on_bezier_curves<F>(
     points: &[Point2D<F>],
     tolerance: F,
@@ -226,6 +257,9 @@ where
     let num_segments = points.len() / 3;
     for i in 0..num_segments {
         let offset = i * 3;
+        if !all_finite(&points[offset..offset + 4]) {
+            continue;
+        }
         get_points_on_bezier_curve_with_splitting(points, offset, tolerance, &mut new_points);
     }
 
@@ -286,6 +320,54 @@ where
     }
 }
 
+/// Generates Bezier Curve parameters of a Catmull-Rom spline passing through given points.
+/// `alpha` selects the parameterization: 0 is uniform, 0.5 centripetal and 1 chordal. Centripetal
+/// and chordal splines do not form cusps or loops on unevenly spaced points. The output starts
+/// with the first point followed by two control points and an end point per segment.
+pub fn catmull_rom_to_bezier<F>(points: &[Point2D<F>], alpha: F) -> Vec<Point2D<F>>
+where
+    F: Float,
+{
+    let n = points.len();
+    if n < 2 {
+        return points.to_vec();
+    }
+    let alpha = alpha.max(F::zero()).min(F::one());
+    // missing neighbours at both ends are mirrored
+    let at = |i: isize| -> Point2D<F> {
+        if i < 0 {
+            points[0] + (points[0] - points[1])
+        } else if i as usize >= n {
+            points[n - 1] + (points[n - 1] - points[n - 2])
+        } else {
+            points[i as usize]
+        }
+    };
+    let two = F::one() + F::one();
+    let three = two + F::one();
+    let control = |from: Point2D<F>, to: Point2D<F>, back: Point2D<F>, d_back: F, d: F| {
+        if d_back <= F::zero() {
+            return from;
+        }
+        let (a, b) = (d_back * d_back, d * d);
+        let sum = to.to_vector() * a - back.to_vector() * b
+            + from.to_vector() * (two * a + three * d_back * d + b);
+        (sum / (three * d_back * (d_back + d))).to_point()
+    };
+
+    let mut out = vec![points[0]];
+    for i in 0..(n - 1) as isize {
+        let (p0, p1, p2, p3) = (at(i - 1), at(i), at(i + 1), at(i + 2));
+        let d1 = (p1 - p0).length().powf(alpha);
+        let d2 = (p2 - p1).length().powf(alpha);
+        let d3 = (p3 - p2).length().powf(alpha);
+        out.push(control(p1, p2, p0, d1, d2));
+        out.push(control(p2, p1, p3, d3, d2));
+        out.push(p2);
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use euclid::point2;
@@ -524,4 +606,94 @@ mod tests {
         let result = super::curve_to_bezier(&input, 0.0).unwrap();
         assert_eq!(expected, result);
     }
+
+    #[test]
+    fn catmull_rom_alpha_avoids_loop() {
+        use euclid::default::Point2D;
+
+        fn cross(a: Point2D<f64>, b: Point2D<f64>, c: Point2D<f64>) -> f64 {
+            (b - a).cross(c - a)
+        }
+        // whether the sampled curve crosses itself
+        fn self_intersects(bezier: &[Point2D<f64>]) -> bool {
+            let samples: Vec<Point2D<f64>> = (0..=64)
+                .map(|i| {
+                    let t = i as f64 / 64.0;
+                    let u = 1.0 - t;
+                    let v = bezier[0].to_vector() * (u * u * u)
+                        + bezier[1].to_vector() * (3.0 * u * u * t)
+                        + bezier[2].to_vector() * (3.0 * u * t * t)
+                        + bezier[3].to_vector() * (t * t * t);
+                    v.to_point()
+                })
+                .collect();
+            let segments: Vec<_> = samples.windows(2).collect();
+            (0..segments.len()).any(|i| {
+                (i + 2..segments.len()).any(|j| {
+                    let (a, b) = (segments[i][0], segments[i][1]);
+                    let (c, d) = (segments[j][0], segments[j][1]);
+                    cross(a, b, c) * cross(a, b, d) < 0.0 && cross(c, d, a) * cross(c, d, b) < 0.0
+                })
+            })
+        }
+
+        // a short segment between two long ones bending back the same way
+        let points = [
+            point2(-10.0, -5.0),
+            point2(0.0, 0.0),
+            point2(1.0, 0.0),
+            point2(11.0, -5.0),
+        ];
+        let uniform = super::catmull_rom_to_bezier(&points, 0.0);
+        let centripetal = super::catmull_rom_to_bezier(&points, 0.5);
+        assert_eq!(uniform.len(), 1 + 3 * 3);
+        assert_eq!(centripetal[3], points[1]);
+        assert_eq!(centripetal[6], points[2]);
+
+        assert!(self_intersects(&uniform[3..7]));
+        assert!(!self_intersects(&centripetal[3..7]));
+    }
+
+    #[test]
+    fn non_finite_points_are_skipped() {
+        let points = [
+            point2(0.0, 0.0),
+            point2(10.0, 10.0),
+            point2(20.0, 10.0),
+            point2(30.0, 0.0),
+            point2(f64::NAN, 5.0),
+            point2(50.0, 10.0),
+            point2(60.0, 0.0),
+        ];
+        let sampled = super::points_on_bezier_curves(&points, 0.1, Some(0.5));
+        assert!(sampled.len() >= 2);
+        assert!(sampled.iter().all(|p| p.x.is_finite() && p.y.is_finite()));
+        assert_eq!(sampled.first(), Some(&point2(0.0, 0.0)));
+        assert_eq!(sampled.last(), Some(&point2(30.0, 0.0)));
+
+        assert_eq!(super::flatness(&points, 3), f64::INFINITY);
+        assert_eq!(
+            super::distance_to_segment_squared(points[4], points[0], points[3]),
+            f64::INFINITY
+        );
+    }
+
+    #[test]
+    fn simplify_preserve_corners_keeps_l_corner() {
+        let mut points: Vec<_> = (0..=10).map(|i| point2(i as f64 * 10.0, 0.0)).collect();
+        points.extend((1..=10).map(|i| point2(100.0, i as f64 * 10.0)));
+
+        assert_eq!(
+            super::simplify(&points, 100.0),
+            vec![point2(0.0, 0.0), point2(100.0, 100.0)]
+        );
+        assert_eq!(
+            super::simplify_preserve_corners(&points, 100.0, 45.0),
+            vec![point2(0.0, 0.0), point2(100.0, 0.0), point2(100.0, 100.0)]
+        );
+        assert_eq!(
+            super::simplify_preserve_corners(&points, 100.0, 120.0),
+            vec![point2(0.0, 0.0), point2(100.0, 100.0)]
+        );
+    }
 }
@@ -0,0 +1,46 @@
+//! This example shows drawing rough shapes in immediate mode, with a RoughContext
+//! painting straight onto a piet render context
+
+use palette::Srgba;
+use piet::{Color, RenderContext};
+use piet_common::kurbo::Rect;
+use piet_common::Device;
+use rough_piet::PietRenderer;
+use roughr::backend::RoughContext;
+use roughr::core::{FillStyle, OptionsBuilder};
+
+const WIDTH: usize = 192;
+const HEIGHT: usize = 108;
+/// For now, assume pixel density (dots per inch)
+const DPI: f32 = 96.;
+
+/// Feature "png" needed for save_to_file() and it's disabled by default for optional dependencies
+/// cargo run --example rough_context --features png
+fn main() {
+    let mut device = Device::new().unwrap();
+    let mut bitmap = device.bitmap_target(WIDTH, HEIGHT, 1.0).unwrap();
+    let mut rc = bitmap.render_context();
+    let background_color = Color::from_hex_str("96C0B7").unwrap();
+    rc.fill(
+        Rect::new(0.0, 0.0, WIDTH as f64, HEIGHT as f64),
+        &background_color,
+    );
+
+    let options = OptionsBuilder::default()
+        .stroke(Srgba::from_components((114u8, 87u8, 82u8, 255u8)).into_format())
+        .fill(Srgba::from_components((254u8, 246u8, 201u8, 255u8)).into_format())
+        .fill_style(FillStyle::Hachure)
+        .fill_weight(DPI * 0.01)
+        .build()
+        .unwrap();
+    let mut renderer = PietRenderer::new(&mut rc);
+    let mut ctx = RoughContext::new(&mut renderer, Some(options));
+    ctx.rect(16.0f32, 24.0, 64.0, 60.0);
+    ctx.circle(136.0f32, 54.0, 60.0);
+    ctx.line(16.0f32, 96.0, 176.0, 96.0);
+
+    rc.finish().unwrap();
+    std::mem::drop(rc);
+
+    bitmap.save_to_file("rough_context.png").expect("file save error");
+}
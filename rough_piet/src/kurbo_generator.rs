@@ -4,11 +4,11 @@ use std::ops::MulAssign;
 use euclid::default::Point2D;
 use euclid::Trig;
 use num_traits::{Float, FromPrimitive};
-use palette::rgb::Rgba;
 use palette::Srgba;
-use piet::kurbo::{self, BezPath, PathEl, Point};
+use piet::kurbo::{self, BezPath, PathEl, Point, Vec2};
 use piet::{Color, LineJoin, RenderContext, StrokeStyle};
-use roughr::core::{Drawable, OpSet, OpSetType, OpType, Options};
+use roughr::backend::{render_op_set, RoughRenderer, StrokeStyle as RoughStrokeStyle};
+use roughr::core::{Drawable, Op, OpSet, OpSetType, OpType, Options};
 use roughr::generator::Generator;
 use roughr::PathSegment;
 
@@ -65,134 +65,189 @@ impl KurboGenerator {
     pub fn new(options: Options) -> Self {
         KurboGenerator { gen: Generator::default(), options: Some(options) }
     }
+
+    /// Replaces the random seed of stored options in place, so subsequent shapes
+    /// get a fresh sketch without reconstructing the generator.
+    pub fn reseed(&mut self, seed: u64) {
+        let options = self.options.get_or_insert_with(Options::default);
+        options.seed = Some(seed);
+        options.randomizer = None;
+    }
 }
 
 impl<F: Float + Trig> KurboDrawable<F> {
+    /// Returns the svg path data of every op set, in drawing order. Quadratic segments are
+    /// written as cubic curves and closed subpaths as a line back to their start.
+    pub fn to_svg_paths(&self) -> Vec<String> {
+        let fixed_decimals = self.options.fixed_decimal_place_digits.map(|d| d as u32);
+        self.sets
+            .iter()
+            .map(|set| {
+                let opset = OpSet {
+                    op_set_type: set.op_set_type.clone(),
+                    ops: bezpath_to_ops(&set.ops),
+                    size: None,
+                    path: None,
+                };
+                Generator::ops_to_path(opset, fixed_decimals)
+                    .trim_end()
+                    .to_string()
+            })
+            .collect()
+    }
+
     pub fn draw(&self, ctx: &mut impl RenderContext) {
+        ctx.save().expect("Failed to save render context");
+        let mut renderer = PietRenderer::new(ctx);
         for set in self.sets.iter() {
-            match set.op_set_type {
-                OpSetType::Path => {
-                    ctx.save().expect("Failed to save render context");
-                    if self.options.stroke_line_dash.is_some() {
-                        let stroke_line_dash =
-                            self.options.stroke_line_dash.clone().unwrap_or(Vec::new());
-                        let mut ss = StrokeStyle::new();
-                        ss.set_dash_pattern(stroke_line_dash.as_slice());
-                        ss.set_dash_offset(self.options.stroke_line_dash_offset.unwrap_or(1.0f64));
-                        ss.set_line_cap(convert_line_cap_from_roughr_to_piet(
-                            self.options.line_cap,
-                        ));
-                        ss.set_line_join(convert_line_join_from_roughr_to_piet(
-                            self.options.line_join,
-                        ));
-
-                        let stroke_color = self
-                            .options
-                            .stroke
-                            .unwrap_or_else(|| Srgba::from_components((1.0, 1.0, 1.0, 1.0)));
-                        let rgb: (f32, f32, f32, f32) = stroke_color.into_components();
-                        ctx.stroke_styled(
-                            set.ops.clone(),
-                            &Color::rgba(rgb.0 as f64, rgb.1 as f64, rgb.2 as f64, rgb.3 as f64),
-                            self.options.stroke_width.unwrap_or(1.0) as f64,
-                            &ss,
-                        );
-                        ctx.restore().expect("Failed to restore render context");
-                    } else {
-                        let stroke_color = self
-                            .options
-                            .stroke
-                            .unwrap_or_else(|| Srgba::new(1.0, 1.0, 1.0, 1.0));
-                        let rgb: (f32, f32, f32, f32) = stroke_color.into_components();
-                        ctx.stroke(
-                            set.ops.clone(),
-                            &Color::rgba(rgb.0 as f64, rgb.1 as f64, rgb.2 as f64, rgb.3 as f64),
-                            self.options.stroke_width.unwrap_or(1.0) as f64,
-                        );
-                        ctx.restore().expect("Failed to restore render context");
-                    }
-                }
-                OpSetType::FillPath => {
-                    ctx.save().expect("Failed to save render context");
-                    match self.shape.as_str() {
-                        "curve" | "polygon" | "path" => {
-                            let fill_color =
-                                self.options.fill.unwrap_or(Rgba::new(1.0, 1.0, 1.0, 1.0));
-                            let rgb: (f32, f32, f32, f32) = fill_color.into_components();
-                            ctx.fill_even_odd(
-                                set.ops.clone(),
-                                &Color::rgba(
-                                    rgb.0 as f64,
-                                    rgb.1 as f64,
-                                    rgb.2 as f64,
-                                    rgb.3 as f64,
-                                ),
-                            )
-                        }
-                        _ => {
-                            let fill_color =
-                                self.options.fill.unwrap_or(Rgba::new(1.0, 1.0, 1.0, 1.0));
-                            let rgb: (f32, f32, f32, f32) = fill_color.into_components();
-                            ctx.fill(
-                                set.ops.clone(),
-                                &Color::rgba(
-                                    rgb.0 as f64,
-                                    rgb.1 as f64,
-                                    rgb.2 as f64,
-                                    rgb.3 as f64,
-                                ),
-                            )
-                        }
-                    }
-                    ctx.restore().expect("Failed to restore render context");
-                }
-                OpSetType::FillSketch => {
-                    let mut fweight = self.options.fill_weight.unwrap_or_default();
-                    if fweight < 0.0 {
-                        fweight = self.options.stroke_width.unwrap_or(1.0) / 2.0;
-                    }
-                    ctx.save().expect("Failed to save render context");
-
-                    if self.options.fill_line_dash.is_some() {
-                        let fill_line_dash =
-                            self.options.fill_line_dash.clone().unwrap_or_default();
-                        let mut ss = StrokeStyle::new();
-                        ss.set_dash_pattern(fill_line_dash.as_slice());
-                        ss.set_dash_offset(self.options.fill_line_dash_offset.unwrap_or(0.0f64));
-                        ss.set_line_cap(convert_line_cap_from_roughr_to_piet(
-                            self.options.line_cap,
-                        ));
-                        ss.set_line_join(convert_line_join_from_roughr_to_piet(
-                            self.options.line_join,
-                        ));
-                        let fill_color = self
-                            .options
-                            .fill
-                            .unwrap_or_else(|| Rgba::new(1.0, 1.0, 1.0, 1.0));
-                        let rgb: (f32, f32, f32, f32) = fill_color.into_components();
-                        ctx.stroke_styled(
-                            set.ops.clone(),
-                            &Color::rgba(rgb.0 as f64, rgb.1 as f64, rgb.2 as f64, rgb.3 as f64),
-                            fweight as f64,
-                            &ss,
-                        );
-                    } else {
-                        let fill_color = self
-                            .options
-                            .fill
-                            .unwrap_or_else(|| Rgba::new(1.0, 1.0, 1.0, 1.0));
-                        let rgb: (f32, f32, f32, f32) = fill_color.into_components();
-                        ctx.stroke(
-                            set.ops.clone(),
-                            &Color::rgba(rgb.0 as f64, rgb.1 as f64, rgb.2 as f64, rgb.3 as f64),
-                            fweight as f64,
-                        );
-                    }
-                    ctx.restore().expect("Failed to restore render context");
-                }
+            let op_set = OpSet {
+                op_set_type: set.op_set_type.clone(),
+                ops: bezpath_to_ops(&set.ops),
+                size: None,
+                path: None,
+            };
+            render_op_set(&op_set, &self.shape, &self.options, &mut renderer);
+        }
+        ctx.restore().expect("Failed to restore render context");
+    }
+}
+
+/// [`RoughRenderer`] painting onto a piet render context, for use with
+/// [`roughr::backend::render_drawable`].
+pub struct PietRenderer<'a, R: RenderContext> {
+    ctx: &'a mut R,
+    path: BezPath,
+}
+
+impl<'a, R: RenderContext> PietRenderer<'a, R> {
+    pub fn new(ctx: &'a mut R) -> Self {
+        PietRenderer { ctx, path: BezPath::new() }
+    }
+}
+
+fn piet_color(color: Srgba) -> Color {
+    let (r, g, b, a): (f32, f32, f32, f32) = color.into_components();
+    Color::rgba(r as f64, g as f64, b as f64, a as f64)
+}
+
+impl<F: Float, R: RenderContext> RoughRenderer<F> for PietRenderer<'_, R> {
+    fn move_to(&mut self, x: F, y: F) {
+        self.path
+            .move_to((x.to_f64().unwrap(), y.to_f64().unwrap()));
+    }
+
+    fn line_to(&mut self, x: F, y: F) {
+        self.path
+            .line_to((x.to_f64().unwrap(), y.to_f64().unwrap()));
+    }
+
+    fn curve_to(&mut self, cp1x: F, cp1y: F, cp2x: F, cp2y: F, x: F, y: F) {
+        self.path.curve_to(
+            (cp1x.to_f64().unwrap(), cp1y.to_f64().unwrap()),
+            (cp2x.to_f64().unwrap(), cp2y.to_f64().unwrap()),
+            (x.to_f64().unwrap(), y.to_f64().unwrap()),
+        );
+    }
+
+    fn close(&mut self) {
+        self.path.close_path();
+    }
+
+    fn stroke(&mut self, color: Srgba, style: &RoughStrokeStyle) {
+        let path = std::mem::take(&mut self.path);
+        let width = style.width as f64;
+        if let Some(taper) = style.taper {
+            self.ctx
+                .fill(tapered_ribbon(&path, width, taper), &piet_color(color));
+            return;
+        }
+        let mut ss = StrokeStyle::new();
+        if let Some(dash) = &style.dash {
+            ss.set_dash_pattern(dash.as_slice());
+            ss.set_dash_offset(style.dash_offset);
+        }
+        ss.set_line_cap(convert_line_cap_from_roughr_to_piet(style.line_cap));
+        ss.set_line_join(convert_line_join_from_roughr_to_piet(style.line_join));
+        self.ctx.stroke_styled(path, &piet_color(color), width, &ss);
+    }
+
+    fn fill(&mut self, color: Srgba, even_odd: bool) {
+        let path = std::mem::take(&mut self.path);
+        if even_odd {
+            self.ctx.fill_even_odd(path, &piet_color(color));
+        } else {
+            self.ctx.fill(path, &piet_color(color));
+        }
+    }
+}
+
+/// Converts a stroke path into a filled ribbon. Ribbon width ramps up from zero over the
+/// first `taper.0` fraction of the total path length and back down to zero over the last
+/// `taper.1` fraction, giving strokes a calligraphic look.
+pub fn tapered_ribbon(path: &BezPath, width: f64, taper: (f32, f32)) -> BezPath {
+    let mut polylines: Vec<Vec<Point>> = vec![];
+    kurbo::flatten(path, 0.1, |el| match el {
+        PathEl::MoveTo(p) => polylines.push(vec![p]),
+        PathEl::LineTo(p) => {
+            if let Some(polyline) = polylines.last_mut() {
+                polyline.push(p);
+            }
+        }
+        PathEl::ClosePath => {
+            if let Some(polyline) = polylines.last_mut() {
+                let first = polyline[0];
+                polyline.push(first);
+            }
+        }
+        _ => {}
+    });
+
+    let total: f64 = polylines
+        .iter()
+        .flat_map(|polyline| polyline.windows(2))
+        .map(|w| w[0].distance(w[1]))
+        .sum();
+    let half_width = width / 2.0;
+    let taper_start = taper.0 as f64 * total;
+    let taper_end = taper.1 as f64 * total;
+    let half_width_at = |travelled: f64| {
+        let mut w = half_width;
+        if taper_start > 0.0 {
+            w = w.min(half_width * travelled / taper_start);
+        }
+        if taper_end > 0.0 {
+            w = w.min(half_width * (total - travelled) / taper_end);
+        }
+        w.max(0.0)
+    };
+
+    let mut ribbon = BezPath::new();
+    let mut travelled = 0.0;
+    for polyline in polylines.iter().filter(|p| p.len() > 1) {
+        let last = polyline.len() - 1;
+        let mut left = Vec::with_capacity(polyline.len());
+        let mut right = Vec::with_capacity(polyline.len());
+        for (i, point) in polyline.iter().enumerate() {
+            if i > 0 {
+                travelled += polyline[i - 1].distance(*point);
             }
+            let direction = polyline[(i + 1).min(last)] - polyline[i.saturating_sub(1)];
+            let length = direction.hypot();
+            let normal = if length > 0.0 {
+                Vec2::new(-direction.y, direction.x) / length
+            } else {
+                Vec2::ZERO
+            };
+            let offset = normal * half_width_at(travelled);
+            left.push(*point + offset);
+            right.push(*point - offset);
         }
+        ribbon.move_to(left[0]);
+        left[1..].iter().for_each(|p| ribbon.line_to(*p));
+        right.iter().rev().for_each(|p| ribbon.line_to(*p));
+        ribbon.close_path();
     }
+    ribbon
 }
 
 fn opset_to_shape<F: Trig + Float + FromPrimitive>(op_set: &OpSet<F>) -> BezPath {
@@ -346,7 +401,15 @@ impl KurboGenerator {
         &self,
         bezier_path: BezPath,
     ) -> KurboDrawable<F> {
-        let segments = bezpath_to_svg_segments(&bezier_path);
+        self.rough_bezpath(&bezier_path)
+    }
+
+    /// Roughens an existing [`BezPath`] through the same pipeline as [`KurboGenerator::path`].
+    pub fn rough_bezpath<F: Trig + Float + FromPrimitive + MulAssign + Display>(
+        &self,
+        path: &BezPath,
+    ) -> KurboDrawable<F> {
+        let segments = bezpath_to_svg_segments(path);
         self.gen
             .path_from_segments(segments, &self.options)
             .to_kurbo_drawable()
@@ -375,6 +438,48 @@ fn convert_line_join_from_roughr_to_piet(
     }
 }
 
+fn bezpath_to_ops(path: &BezPath) -> Vec<Op<f64>> {
+    let mut ops = vec![];
+    let mut start = Point::ZERO;
+    let mut current = Point::ZERO;
+    for el in path.elements() {
+        match *el {
+            PathEl::MoveTo(p) => {
+                ops.push(Op { op: OpType::Move, data: vec![p.x, p.y] });
+                start = p;
+                current = p;
+            }
+            PathEl::LineTo(p) => {
+                ops.push(Op { op: OpType::LineTo, data: vec![p.x, p.y] });
+                current = p;
+            }
+            PathEl::QuadTo(q, p) => {
+                let c1 = current.lerp(q, 2.0 / 3.0);
+                let c2 = p.lerp(q, 2.0 / 3.0);
+                ops.push(Op {
+                    op: OpType::BCurveTo,
+                    data: vec![c1.x, c1.y, c2.x, c2.y, p.x, p.y],
+                });
+                current = p;
+            }
+            PathEl::CurveTo(c1, c2, p) => {
+                ops.push(Op {
+                    op: OpType::BCurveTo,
+                    data: vec![c1.x, c1.y, c2.x, c2.y, p.x, p.y],
+                });
+                current = p;
+            }
+            PathEl::ClosePath => {
+                if current != start {
+                    ops.push(Op { op: OpType::LineTo, data: vec![start.x, start.y] });
+                }
+                current = start;
+            }
+        }
+    }
+    ops
+}
+
 pub fn bezpath_to_svg_segments(path: &BezPath) -> Vec<PathSegment> {
     let mut segments = Vec::new();
 
@@ -414,3 +519,156 @@ pub fn bezpath_to_svg_segments(path: &BezPath) -> Vec<PathSegment> {
 
     segments
 }
+
+#[cfg(test)]
+mod test {
+    use piet::kurbo::{self, BezPath, PathEl, Point, Shape};
+    use piet::{NullRenderContext, RenderContext};
+    use roughr::backend::{RoughRenderer, StrokeStyle};
+    use roughr::core::{FillStyle, OptionsBuilder};
+    use roughr::{PathParser, Srgba};
+
+    use super::{tapered_ribbon, KurboGenerator, PietRenderer};
+
+    #[test]
+    fn tapered_ribbon_has_zero_width_at_endpoints() {
+        let mut path = BezPath::new();
+        path.move_to((0.0, 0.0));
+        path.line_to((50.0, 0.0));
+        path.line_to((100.0, 0.0));
+
+        let ribbon = tapered_ribbon(&path, 4.0, (0.25, 0.25));
+        let points: Vec<Point> = ribbon
+            .elements()
+            .iter()
+            .filter_map(|el| match el {
+                PathEl::MoveTo(p) | PathEl::LineTo(p) => Some(*p),
+                _ => None,
+            })
+            .collect();
+        // left side goes forward, right side comes back
+        let start_left = points[0];
+        let start_right = points[points.len() - 1];
+        let end_left = points[points.len() / 2 - 1];
+        let end_right = points[points.len() / 2];
+        assert!(start_left.distance(start_right) < 1e-6);
+        assert!(end_left.distance(end_right) < 1e-6);
+        assert!(points.iter().any(|p| (p.y - 2.0).abs() < 1e-6));
+    }
+
+    #[test]
+    fn reseed_changes_geometry() {
+        let mut generator = KurboGenerator::default();
+        let before = generator.line(0.0f32, 0.0, 100.0, 100.0);
+        generator.reseed(42);
+        let after = generator.line(0.0f32, 0.0, 100.0, 100.0);
+        generator.reseed(42);
+        let again = generator.line(0.0f32, 0.0, 100.0, 100.0);
+
+        assert_ne!(before.sets[0].ops, after.sets[0].ops);
+        assert_eq!(after.sets[0].ops, again.sets[0].ops);
+    }
+
+    #[test]
+    fn rough_bezpath_roughens_rectangle() {
+        let rect = kurbo::Rect::new(10.0, 10.0, 60.0, 40.0).to_path(0.1);
+        let drawable = KurboGenerator::default().rough_bezpath::<f32>(&rect);
+
+        assert_eq!(drawable.shape, "path");
+        let elements = drawable.sets[0].ops.elements();
+        assert!(elements.iter().any(|el| matches!(el, PathEl::CurveTo(..))));
+        assert!(!elements.iter().any(|el| matches!(el, PathEl::ClosePath)));
+    }
+
+    #[test]
+    fn open_arc_is_drawn_with_curves() {
+        let drawable = KurboGenerator::default().arc(
+            50.0f32,
+            50.0,
+            80.0,
+            80.0,
+            std::f32::consts::PI * 0.75,
+            std::f32::consts::PI * 2.25,
+            false,
+        );
+
+        assert_eq!(drawable.shape, "arc");
+        let elements = drawable.sets[0].ops.elements();
+        assert!(elements.iter().any(|el| matches!(el, PathEl::CurveTo(..))));
+        assert!(!elements.iter().any(|el| matches!(el, PathEl::ClosePath)));
+    }
+
+    #[test]
+    fn to_svg_paths_are_parseable() {
+        let options = OptionsBuilder::default()
+            .fill(Srgba::new(1.0, 0.0, 0.0, 1.0))
+            .fill_style(FillStyle::Hachure)
+            .build()
+            .unwrap();
+        let drawable = KurboGenerator::new(options).rectangle(10.0f32, 10.0, 80.0, 40.0);
+        let paths = drawable.to_svg_paths();
+
+        assert_eq!(paths.len(), drawable.sets.len());
+        for d in paths.iter() {
+            let segments: Vec<_> = PathParser::from(d.as_str()).collect();
+            assert!(!segments.is_empty());
+            assert!(segments.iter().all(|segment| segment.is_ok()), "{}", d);
+        }
+    }
+
+    #[test]
+    fn piet_renderer_builds_path_until_painted() {
+        let mut ctx = NullRenderContext::new();
+        let mut renderer = PietRenderer::new(&mut ctx);
+        renderer.move_to(0.0f32, 0.0);
+        renderer.line_to(10.0, 0.0);
+        renderer.curve_to(10.0, 5.0, 5.0, 10.0, 0.0, 10.0);
+        RoughRenderer::<f32>::close(&mut renderer);
+
+        assert_eq!(
+            renderer.path.elements(),
+            &[
+                PathEl::MoveTo(Point::new(0.0, 0.0)),
+                PathEl::LineTo(Point::new(10.0, 0.0)),
+                PathEl::CurveTo(
+                    Point::new(10.0, 5.0),
+                    Point::new(5.0, 10.0),
+                    Point::new(0.0, 10.0)
+                ),
+                PathEl::ClosePath,
+            ]
+        );
+
+        let style = StrokeStyle {
+            width: 2.0,
+            line_cap: None,
+            line_join: None,
+            dash: Some(vec![4.0, 2.0]),
+            dash_offset: 0.0,
+            taper: Some((0.3, 0.3)),
+        };
+        RoughRenderer::<f32>::stroke(&mut renderer, Srgba::new(0.0, 0.0, 0.0, 1.0), &style);
+        assert!(renderer.path.elements().is_empty());
+
+        renderer.move_to(0.0f32, 0.0);
+        renderer.line_to(10.0, 10.0);
+        RoughRenderer::<f32>::fill(&mut renderer, Srgba::new(0.0, 0.0, 0.0, 1.0), true);
+        assert!(renderer.path.elements().is_empty());
+    }
+
+    #[test]
+    fn kurbo_drawable_draws_through_piet_renderer() {
+        let options = OptionsBuilder::default()
+            .fill(Srgba::new(1.0, 0.0, 0.0, 1.0))
+            .fill_style(FillStyle::Hachure)
+            .stroke_line_dash(vec![5.0, 3.0])
+            .fill_line_dash(vec![2.0, 2.0])
+            .build()
+            .unwrap();
+        let drawable = KurboGenerator::new(options).rectangle(10.0f32, 10.0, 80.0, 40.0);
+        let mut ctx = NullRenderContext::new();
+
+        drawable.draw(&mut ctx);
+        ctx.finish().unwrap();
+    }
+}
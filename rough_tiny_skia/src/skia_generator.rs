@@ -5,7 +5,8 @@ use euclid::default::Point2D;
 use euclid::Trig;
 use num_traits::{Float, FromPrimitive};
 use palette::Srgba;
-use roughr::core::{Drawable, OpSet, OpSetType, OpType, Options};
+use roughr::backend::{render_op_set, RoughRenderer, StrokeStyle};
+use roughr::core::{Drawable, Op, OpSet, OpSetType, OpType, Options};
 use roughr::generator::Generator;
 use tiny_skia::{
     FillRule,
@@ -14,7 +15,9 @@ use tiny_skia::{
     Paint,
     Path,
     PathBuilder,
+    PathSegment,
     PixmapMut,
+    Point,
     Stroke,
     StrokeDash,
     Transform,
@@ -73,185 +76,161 @@ impl SkiaGenerator {
     pub fn new(options: Options) -> Self {
         SkiaGenerator { gen: Generator::default(), options: Some(options) }
     }
+
+    /// Replaces the random seed of stored options in place, so subsequent shapes
+    /// get a fresh sketch without reconstructing the generator.
+    pub fn reseed(&mut self, seed: u64) {
+        let options = self.options.get_or_insert_with(Options::default);
+        options.seed = Some(seed);
+        options.randomizer = None;
+    }
 }
 
 impl<F: Float + Trig> SkiaDrawable<F> {
     pub fn draw(&self, ctx: &mut PixmapMut) {
+        let mut renderer = SkiaRenderer::new(ctx);
         for set in self.sets.iter() {
-            match set.op_set_type {
-                OpSetType::Path => {
-                    if self.options.stroke_line_dash.is_some() {
-                        let mut stroke = Stroke {
-                            width: self.options.stroke_width.unwrap_or(1.0),
-                            line_cap: convert_line_cap_from_roughr_to_piet(self.options.line_cap),
-                            line_join: convert_line_join_from_roughr_to_piet(
-                                self.options.line_join,
-                            ),
-                            ..Stroke::default()
-                        };
-                        let stroke_line_dash = self
-                            .options
-                            .stroke_line_dash
-                            .clone()
-                            .unwrap_or(Vec::new())
-                            .iter()
-                            .map(|&a| a as f32)
-                            .collect();
-
-                        stroke.dash = StrokeDash::new(
-                            stroke_line_dash,
-                            self.options.stroke_line_dash_offset.unwrap_or(1.0f64) as f32,
-                        );
-
-                        let stroke_color = self
-                            .options
-                            .stroke
-                            .unwrap_or_else(|| Srgba::from_components((1.0, 1.0, 1.0, 1.0)));
-                        let stroke_color_components: (u8, u8, u8, u8) =
-                            stroke_color.into_format().into_components();
-
-                        let mut paint = Paint::default();
-                        paint.set_color_rgba8(
-                            stroke_color_components.0,
-                            stroke_color_components.1,
-                            stroke_color_components.2,
-                            stroke_color_components.3,
-                        );
-                        paint.anti_alias = true;
-
-                        ctx.stroke_path(&set.ops, &paint, &stroke, Transform::identity(), None);
-                    } else {
-                        let mut stroke = Stroke::default();
-                        stroke.width = self.options.stroke_width.unwrap_or(1.0);
-
-                        let stroke_color = self
-                            .options
-                            .stroke
-                            .unwrap_or_else(|| Srgba::from_components((1.0, 1.0, 1.0, 1.0)));
-                        let stroke_color_components: (u8, u8, u8, u8) =
-                            stroke_color.into_format().into_components();
-
-                        let mut paint = Paint::default();
-                        paint.set_color_rgba8(
-                            stroke_color_components.0,
-                            stroke_color_components.1,
-                            stroke_color_components.2,
-                            stroke_color_components.3,
-                        );
-                        paint.anti_alias = true;
-
-                        ctx.stroke_path(&set.ops, &paint, &stroke, Transform::identity(), None);
-                    }
-                }
-                OpSetType::FillPath => {
-                    let fill_color = self
-                        .options
-                        .fill
-                        .unwrap_or(Srgba::from_components((1.0, 1.0, 1.0, 1.0)));
-                    let fill_color_components: (u8, u8, u8, u8) =
-                        fill_color.into_format().into_components();
-
-                    let mut paint = Paint::default();
-                    paint.set_color_rgba8(
-                        fill_color_components.0,
-                        fill_color_components.1,
-                        fill_color_components.2,
-                        fill_color_components.3,
-                    );
-                    paint.anti_alias = true;
-                    match self.shape.as_str() {
-                        "curve" | "polygon" | "path" => {
-                            ctx.fill_path(
-                                &set.ops,
-                                &paint,
-                                FillRule::EvenOdd,
-                                Transform::identity(),
-                                None,
-                            );
-                        }
-                        _ => {
-                            ctx.fill_path(
-                                &set.ops,
-                                &paint,
-                                FillRule::Winding,
-                                Transform::identity(),
-                                None,
-                            );
-                        }
-                    }
-                }
-                OpSetType::FillSketch => {
-                    let mut fweight = self.options.fill_weight.unwrap_or_default();
-                    if fweight < 0.0 {
-                        fweight = self.options.stroke_width.unwrap_or(1.0) / 2.0;
-                    }
-
-                    if self.options.fill_line_dash.is_some() {
-                        let mut stroke = Stroke::default();
-                        stroke.width = self.options.fill_weight.unwrap_or(1.0);
-                        stroke.line_cap =
-                            convert_line_cap_from_roughr_to_piet(self.options.line_cap);
-                        stroke.line_join =
-                            convert_line_join_from_roughr_to_piet(self.options.line_join);
-                        let fill_line_dash = self
-                            .options
-                            .fill_line_dash
-                            .clone()
-                            .unwrap_or(Vec::new())
-                            .iter()
-                            .map(|&a| a as f32)
-                            .collect();
-
-                        stroke.dash = StrokeDash::new(
-                            fill_line_dash,
-                            self.options.fill_line_dash_offset.unwrap_or(1.0f64) as f32,
-                        );
-
-                        let fill_color = self
-                            .options
-                            .fill
-                            .unwrap_or(Srgba::from_components((1.0, 1.0, 1.0, 1.0)));
-                        let fill_color_components: (u8, u8, u8, u8) =
-                            fill_color.into_format().into_components();
-
-                        let mut paint = Paint::default();
-                        paint.set_color_rgba8(
-                            fill_color_components.0,
-                            fill_color_components.1,
-                            fill_color_components.2,
-                            fill_color_components.3,
-                        );
-                        paint.anti_alias = true;
-                        ctx.stroke_path(&set.ops, &paint, &stroke, Transform::identity(), None);
-                    } else {
-                        let mut stroke = Stroke::default();
-                        stroke.width = self.options.fill_weight.unwrap_or(1.0);
-                        stroke.line_cap =
-                            convert_line_cap_from_roughr_to_piet(self.options.line_cap);
-                        stroke.line_join =
-                            convert_line_join_from_roughr_to_piet(self.options.line_join);
-
-                        let fill_color = self
-                            .options
-                            .fill
-                            .unwrap_or(Srgba::from_components((1.0, 1.0, 1.0, 1.0)));
-                        let fill_color_components: (u8, u8, u8, u8) =
-                            fill_color.into_format().into_components();
-
-                        let mut paint = Paint::default();
-                        paint.set_color_rgba8(
-                            fill_color_components.0,
-                            fill_color_components.1,
-                            fill_color_components.2,
-                            fill_color_components.3,
-                        );
-                        paint.anti_alias = true;
-                        ctx.stroke_path(&set.ops, &paint, &stroke, Transform::identity(), None);
-                    }
+            let op_set = OpSet {
+                op_set_type: set.op_set_type.clone(),
+                ops: path_to_ops(&set.ops),
+                size: None,
+                path: None,
+            };
+            render_op_set(&op_set, &self.shape, &self.options, &mut renderer);
+        }
+    }
+}
+
+/// [`RoughRenderer`] painting onto a tiny-skia pixmap, for use with
+/// [`roughr::backend::render_drawable`].
+pub struct SkiaRenderer<'a, 'b> {
+    pixmap: &'a mut PixmapMut<'b>,
+    path: PathBuilder,
+}
+
+impl<'a, 'b> SkiaRenderer<'a, 'b> {
+    pub fn new(pixmap: &'a mut PixmapMut<'b>) -> Self {
+        SkiaRenderer { pixmap, path: PathBuilder::new() }
+    }
+
+    fn paint(color: Srgba) -> Paint<'static> {
+        let (r, g, b, a): (u8, u8, u8, u8) = color.into_format().into_components();
+        let mut paint = Paint::default();
+        paint.set_color_rgba8(r, g, b, a);
+        paint.anti_alias = true;
+        paint
+    }
+}
+
+impl<F: Float> RoughRenderer<F> for SkiaRenderer<'_, '_> {
+    fn move_to(&mut self, x: F, y: F) {
+        self.path.move_to(x.to_f32().unwrap(), y.to_f32().unwrap());
+    }
+
+    fn line_to(&mut self, x: F, y: F) {
+        self.path.line_to(x.to_f32().unwrap(), y.to_f32().unwrap());
+    }
+
+    fn curve_to(&mut self, cp1x: F, cp1y: F, cp2x: F, cp2y: F, x: F, y: F) {
+        self.path.cubic_to(
+            cp1x.to_f32().unwrap(),
+            cp1y.to_f32().unwrap(),
+            cp2x.to_f32().unwrap(),
+            cp2y.to_f32().unwrap(),
+            x.to_f32().unwrap(),
+            y.to_f32().unwrap(),
+        );
+    }
+
+    fn close(&mut self) {
+        self.path.close();
+    }
+
+    fn stroke(&mut self, color: Srgba, style: &StrokeStyle) {
+        if let Some(path) = std::mem::take(&mut self.path).finish() {
+            let stroke = Stroke {
+                width: style.width,
+                line_cap: convert_line_cap_from_roughr_to_piet(style.line_cap),
+                line_join: convert_line_join_from_roughr_to_piet(style.line_join),
+                dash: style.dash.as_ref().and_then(|dash| {
+                    StrokeDash::new(
+                        dash.iter().map(|&a| a as f32).collect(),
+                        style.dash_offset as f32,
+                    )
+                }),
+                ..Stroke::default()
+            };
+            self.pixmap.stroke_path(
+                &path,
+                &Self::paint(color),
+                &stroke,
+                Transform::identity(),
+                None,
+            );
+        }
+    }
+
+    fn fill(&mut self, color: Srgba, even_odd: bool) {
+        if let Some(path) = std::mem::take(&mut self.path).finish() {
+            let rule = if even_odd {
+                FillRule::EvenOdd
+            } else {
+                FillRule::Winding
+            };
+            self.pixmap.fill_path(
+                &path,
+                &Self::paint(color),
+                rule,
+                Transform::identity(),
+                None,
+            );
+        }
+    }
+}
+
+fn path_to_ops(path: &Path) -> Vec<Op<f32>> {
+    let mut ops = vec![];
+    let mut start = Point::zero();
+    let mut current = Point::zero();
+    for segment in path.segments() {
+        match segment {
+            PathSegment::MoveTo(p) => {
+                ops.push(Op { op: OpType::Move, data: vec![p.x, p.y] });
+                start = p;
+                current = p;
+            }
+            PathSegment::LineTo(p) => {
+                ops.push(Op { op: OpType::LineTo, data: vec![p.x, p.y] });
+                current = p;
+            }
+            PathSegment::QuadTo(q, p) => {
+                let c1 = (
+                    current.x + (q.x - current.x) * 2.0 / 3.0,
+                    current.y + (q.y - current.y) * 2.0 / 3.0,
+                );
+                let c2 = (p.x + (q.x - p.x) * 2.0 / 3.0, p.y + (q.y - p.y) * 2.0 / 3.0);
+                ops.push(Op {
+                    op: OpType::BCurveTo,
+                    data: vec![c1.0, c1.1, c2.0, c2.1, p.x, p.y],
+                });
+                current = p;
+            }
+            PathSegment::CubicTo(c1, c2, p) => {
+                ops.push(Op {
+                    op: OpType::BCurveTo,
+                    data: vec![c1.x, c1.y, c2.x, c2.y, p.x, p.y],
+                });
+                current = p;
+            }
+            PathSegment::Close => {
+                if current != start {
+                    ops.push(Op { op: OpType::LineTo, data: vec![start.x, start.y] });
                 }
+                current = start;
             }
         }
     }
+    ops
 }
 
 fn opset_to_shape<F: Trig + Float + FromPrimitive>(op_set: &OpSet<F>) -> Path {
@@ -415,3 +394,122 @@ fn convert_line_join_from_roughr_to_piet(
         None => LineJoin::Miter,
     }
 }
+
+#[cfg(test)]
+mod test {
+    use palette::Srgba;
+    use roughr::backend::render_drawable;
+    use roughr::core::{FillStyle, LineCap, OpSetType, OptionsBuilder};
+    use roughr::generator::Generator;
+    use tiny_skia::Pixmap;
+
+    use super::{SkiaGenerator, SkiaRenderer};
+
+    #[test]
+    fn stroke_line_dash_cycles_pattern() {
+        let options = OptionsBuilder::default()
+            .roughness(0.0)
+            .disable_multi_stroke(true)
+            .stroke_width(2.0)
+            .line_cap(LineCap::Butt)
+            .stroke_line_dash(vec![10.0, 5.0, 2.0, 5.0])
+            .stroke_line_dash_offset(0.0)
+            .build()
+            .unwrap();
+        let line = SkiaGenerator::new(options).line(0.0f32, 10.0, 64.0, 10.0);
+        let mut pixmap = Pixmap::new(64, 20).unwrap();
+        line.draw(&mut pixmap.as_mut());
+
+        let mut runs: Vec<(bool, usize)> = vec![];
+        for x in 0..64 {
+            let on = pixmap.pixel(x, 10).unwrap().alpha() > 127;
+            match runs.last_mut() {
+                Some((state, len)) if *state == on => *len += 1,
+                _ => runs.push((on, 1)),
+            }
+        }
+        assert_eq!(
+            runs,
+            vec![
+                (true, 10),
+                (false, 5),
+                (true, 2),
+                (false, 5),
+                (true, 10),
+                (false, 5),
+                (true, 2),
+                (false, 5),
+                (true, 10),
+                (false, 5),
+                (true, 2),
+                (false, 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn renderer_paints_filled_rectangle() {
+        let options = OptionsBuilder::default()
+            .stroke(Srgba::new(0.0, 0.0, 1.0, 1.0))
+            .fill(Srgba::new(1.0, 0.0, 0.0, 1.0))
+            .fill_style(FillStyle::Solid)
+            .build()
+            .unwrap();
+        let drawable = Generator::default().rectangle(8.0f32, 8.0, 48.0, 48.0, &Some(options));
+        let mut pixmap = Pixmap::new(64, 64).unwrap();
+        render_drawable(&drawable, &mut SkiaRenderer::new(&mut pixmap.as_mut()));
+
+        let center = pixmap.pixel(32, 32).unwrap();
+        assert_eq!((center.red(), center.blue(), center.alpha()), (255, 0, 255));
+        assert_eq!(pixmap.pixel(1, 1).unwrap().alpha(), 0);
+    }
+
+    #[test]
+    fn hachure_as_fill_cross_hatch_covers_intersections() {
+        let options = OptionsBuilder::default()
+            .roughness(0.0)
+            .stroke(Srgba::new(0.0, 0.0, 1.0, 0.0))
+            .fill(Srgba::new(1.0, 0.0, 0.0, 1.0))
+            .fill_style(FillStyle::CrossHatch)
+            .hachure_angle(0.0)
+            .hachure_gap(8.0)
+            .fill_weight(3.0)
+            .hachure_as_fill(true)
+            .build()
+            .unwrap();
+        let drawable = SkiaGenerator::new(options).rectangle(8.0f32, 8.0, 48.0, 48.0);
+        let quads: Vec<Vec<(f32, f32)>> = drawable
+            .sets
+            .iter()
+            .filter(|set| set.op_set_type == OpSetType::FillPath)
+            .flat_map(|set| super::path_to_ops(&set.ops))
+            .map(|op| (op.data[0], op.data[1]))
+            .collect::<Vec<_>>()
+            .chunks(4)
+            .map(|quad| quad.to_vec())
+            .collect();
+        let center = |quad: &Vec<(f32, f32)>| {
+            (
+                quad.iter().map(|p| p.0).sum::<f32>() / 4.0,
+                quad.iter().map(|p| p.1).sum::<f32>() / 4.0,
+            )
+        };
+        let horizontal = quads
+            .iter()
+            .find(|q| (q[0].1 - q[1].1).abs() < 1e-3)
+            .map(center)
+            .unwrap();
+        let vertical = quads
+            .iter()
+            .find(|q| (q[0].0 - q[1].0).abs() < 1e-3)
+            .map(center)
+            .unwrap();
+        let mut pixmap = Pixmap::new(64, 64).unwrap();
+        drawable.draw(&mut pixmap.as_mut());
+
+        let crossing = pixmap
+            .pixel(vertical.0 as u32, horizontal.1 as u32)
+            .unwrap();
+        assert_eq!((crossing.red(), crossing.alpha()), (255, 255));
+    }
+}
@@ -0,0 +1,232 @@
+//! Backend agnostic replay of [`Drawable`]s.
+//!
+//! Adapters implement [`RoughRenderer`] for their drawing surface and call
+//! [`render_drawable`], which walks the op sets in order and decides how each one is
+//! stroked or filled, so every backend paints the same shapes the same way.
+
+use euclid::Trig;
+use num_traits::{Float, FromPrimitive};
+use palette::Srgba;
+
+use crate::core::{Drawable, LineCap, LineJoin, OpSet, OpSetType, OpType, Options};
+use crate::generator::Generator;
+
+/// How [`RoughRenderer::stroke`] draws the outline of a path.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StrokeStyle {
+    pub width: f32,
+    pub line_cap: Option<LineCap>,
+    pub line_join: Option<LineJoin>,
+    /// Dash pattern with an even number of entries, or `None` for a solid line.
+    pub dash: Option<Vec<f64>>,
+    pub dash_offset: f64,
+    /// Narrows the stroke towards both ends, see `Options::taper`. Backends that cannot
+    /// taper draw a plain stroke.
+    pub taper: Option<(f32, f32)>,
+}
+
+/// A drawing surface that can build a path and paint it.
+pub trait RoughRenderer<F> {
+    fn move_to(&mut self, x: F, y: F);
+    fn line_to(&mut self, x: F, y: F);
+    fn curve_to(&mut self, cp1x: F, cp1y: F, cp2x: F, cp2y: F, x: F, y: F);
+    /// Closes the current subpath.
+    fn close(&mut self);
+    /// Strokes the path built since the last paint call, then starts a new path.
+    fn stroke(&mut self, color: Srgba, style: &StrokeStyle);
+    /// Fills the path built since the last paint call, then starts a new path.
+    /// `even_odd` selects the even-odd fill rule instead of nonzero winding.
+    fn fill(&mut self, color: Srgba, even_odd: bool);
+}
+
+/// Replays `drawable` onto `renderer`, one op set after the other with [`render_op_set`].
+pub fn render_drawable<F, R>(drawable: &Drawable<F>, renderer: &mut R)
+where
+    F: Float + Trig + FromPrimitive,
+    R: RoughRenderer<F>,
+{
+    for set in drawable.sets.iter() {
+        render_op_set(set, &drawable.shape, &drawable.options, renderer);
+    }
+}
+
+/// Replays one op set of a drawable of kind `shape` drawn with `o`. Outlines are stroked
+/// with the stroke color, width, caps, joins, dashes and taper. Sketched fills are stroked
+/// with the fill color and `fill_weight` (half the stroke width when unset), dashed by
+/// `fill_line_dash` unless the filler already dashed them. Solid fills use the rule given
+/// by `Options::fill_path_even_odd`.
+pub fn render_op_set<F, R>(set: &OpSet<F>, shape: &str, o: &Options, renderer: &mut R)
+where
+    F: Float + Trig + FromPrimitive,
+    R: RoughRenderer<F>,
+{
+    let white = Srgba::new(1.0, 1.0, 1.0, 1.0);
+    let stroke_width = o.stroke_width.unwrap_or(1.0);
+    for op in set.ops.iter() {
+        let d = &op.data;
+        match op.op {
+            OpType::Move => renderer.move_to(d[0], d[1]),
+            OpType::LineTo => renderer.line_to(d[0], d[1]),
+            OpType::BCurveTo => renderer.curve_to(d[0], d[1], d[2], d[3], d[4], d[5]),
+        }
+    }
+    match set.op_set_type {
+        OpSetType::Path => {
+            let style = StrokeStyle {
+                width: stroke_width,
+                line_cap: o.line_cap,
+                line_join: o.line_join,
+                dash: o.stroke_dash_pattern(),
+                dash_offset: o.stroke_line_dash_offset.unwrap_or(1.0),
+                taper: o.taper,
+            };
+            renderer.stroke(o.stroke.unwrap_or(white), &style);
+        }
+        OpSetType::FillPath => {
+            renderer.close();
+            renderer.fill(o.fill.unwrap_or(white), o.fill_path_even_odd(shape));
+        }
+        OpSetType::FillSketch => {
+            let width = match o.fill_weight {
+                Some(weight) if weight >= 0.0 => weight,
+                _ => stroke_width / 2.0,
+            };
+            let dash = if o.fill_dashed_by_filler() {
+                None
+            } else {
+                o.fill_line_dash.clone()
+            };
+            let style = StrokeStyle {
+                width,
+                line_cap: o.line_cap,
+                line_join: o.line_join,
+                dash,
+                dash_offset: o.fill_line_dash_offset.unwrap_or(0.0),
+                taper: None,
+            };
+            renderer.stroke(o.fill.unwrap_or(white), &style);
+        }
+    }
+}
+
+/// Immediate mode drawing: each call generates a shape with the wrapped [`Generator`] and
+/// renders it right away.
+pub struct RoughContext<'a, R> {
+    generator: Generator,
+    options: Option<Options>,
+    renderer: &'a mut R,
+}
+
+impl<'a, R> RoughContext<'a, R> {
+    /// Draws onto `renderer` using `options` for every shape, or the generator defaults when
+    /// `None`.
+    pub fn new(renderer: &'a mut R, options: Option<Options>) -> Self {
+        RoughContext { generator: Generator::default(), options, renderer }
+    }
+
+    pub fn rect<F>(&mut self, x: F, y: F, width: F, height: F)
+    where
+        F: Float + Trig + FromPrimitive,
+        R: RoughRenderer<F>,
+    {
+        let drawable = self.generator.rectangle(x, y, width, height, &self.options);
+        render_drawable(&drawable, self.renderer);
+    }
+
+    pub fn circle<F>(&mut self, x: F, y: F, diameter: F)
+    where
+        F: Float + Trig + FromPrimitive,
+        R: RoughRenderer<F>,
+    {
+        let drawable = self.generator.circle(x, y, diameter, &self.options);
+        render_drawable(&drawable, self.renderer);
+    }
+
+    pub fn line<F>(&mut self, x1: F, y1: F, x2: F, y2: F)
+    where
+        F: Float + Trig + FromPrimitive,
+        R: RoughRenderer<F>,
+    {
+        let drawable = self.generator.line(x1, y1, x2, y2, &self.options);
+        render_drawable(&drawable, self.renderer);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use palette::Srgba;
+
+    use super::{render_drawable, RoughContext, RoughRenderer, StrokeStyle};
+    use crate::core::{FillStyle, OptionsBuilder};
+    use crate::generator::Generator;
+
+    #[derive(Default)]
+    struct Recorder {
+        calls: Vec<String>,
+    }
+
+    impl RoughRenderer<f32> for Recorder {
+        fn move_to(&mut self, _x: f32, _y: f32) {
+            self.calls.push("move_to".into());
+        }
+        fn line_to(&mut self, _x: f32, _y: f32) {
+            self.calls.push("line_to".into());
+        }
+        fn curve_to(&mut self, _: f32, _: f32, _: f32, _: f32, _: f32, _: f32) {
+            self.calls.push("curve_to".into());
+        }
+        fn close(&mut self) {
+            self.calls.push("close".into());
+        }
+        fn stroke(&mut self, _color: Srgba, style: &StrokeStyle) {
+            self.calls.push(format!("stroke {}", style.width));
+        }
+        fn fill(&mut self, _color: Srgba, even_odd: bool) {
+            self.calls.push(format!("fill even_odd={}", even_odd));
+        }
+    }
+
+    #[test]
+    fn filled_rectangle_fills_before_stroking() {
+        let options = OptionsBuilder::default()
+            .fill(Srgba::new(1.0, 0.0, 0.0, 1.0))
+            .fill_style(FillStyle::Solid)
+            .stroke_width(2.0)
+            .build()
+            .unwrap();
+        let drawable = Generator::default().rectangle(0.0f32, 0.0, 20.0, 10.0, &Some(options));
+        let mut recorder = Recorder::default();
+        render_drawable(&drawable, &mut recorder);
+
+        let paints: Vec<&String> = recorder
+            .calls
+            .iter()
+            .filter(|call| call.starts_with("fill") || call.starts_with("stroke"))
+            .collect();
+        assert_eq!(paints, ["fill even_odd=true", "stroke 2"]);
+        assert_eq!(recorder.calls[0], "move_to");
+        let close = recorder.calls.iter().position(|c| c == "close").unwrap();
+        assert_eq!(recorder.calls[close + 1], "fill even_odd=true");
+        assert!(recorder.calls.iter().any(|c| c == "curve_to"));
+    }
+
+    #[test]
+    fn rough_context_draws_each_call() {
+        let options = OptionsBuilder::default()
+            .fill(Srgba::new(1.0, 0.0, 0.0, 1.0))
+            .fill_style(FillStyle::Hachure)
+            .build()
+            .unwrap();
+        let mut recorder = Recorder::default();
+        let mut ctx = RoughContext::new(&mut recorder, Some(options));
+        ctx.rect(0.0f32, 0.0, 20.0, 10.0);
+        ctx.line(0.0f32, 0.0, 20.0, 10.0);
+
+        let paints: Vec<&String> = recorder
+            .calls
+            .iter()
+            .filter(|call| call.starts_with("fill") || call.starts_with("stroke"))
+            .collect();
+        assert_eq!(paints, ["stroke 0.5", "stroke 1", "stroke 1"]);
+    }
+}
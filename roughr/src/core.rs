@@ -1,10 +1,17 @@
-use euclid::default::Point2D;
+use std::fmt::Display;
+use std::ops::MulAssign;
+
+use euclid::default::{Box2D, Point2D};
 use euclid::Trig;
 use num_traits::{Float, FromPrimitive};
 use palette::Srgba;
+use points_on_curve::points_on_bezier_curves;
 use rand::rngs::StdRng;
 use rand::{random, Rng, SeedableRng};
 
+use crate::generator::{export_opset, Generator};
+use crate::geometry::{clip_polygon, clip_segment_to_polygon};
+
 pub struct Space;
 
 pub struct Config {
@@ -25,6 +32,20 @@ pub enum FillStyle {
     Dots,
     Dashed,
     ZigZagLine,
+    Concentric,
+}
+impl FillStyle {
+    /// Every fill style, in declaration order.
+    pub const ALL: &'static [FillStyle] = &[
+        FillStyle::Solid,
+        FillStyle::Hachure,
+        FillStyle::ZigZag,
+        FillStyle::CrossHatch,
+        FillStyle::Dots,
+        FillStyle::Dashed,
+        FillStyle::ZigZagLine,
+        FillStyle::Concentric,
+    ];
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
@@ -56,6 +77,25 @@ impl Default for LineJoin {
     }
 }
 
+/// Shape of each dot drawn by the dots fill style.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum DotShape {
+    #[default]
+    Circle,
+    Square,
+    Cross,
+}
+
+/// How the dots fill style places its dots.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum DotDistribution {
+    /// Dots along evenly spaced scan lines, jittered a little.
+    #[default]
+    Grid,
+    /// Seeded Poisson-disk sampling, which keeps dots `hachure_gap` apart without a grid.
+    Poisson,
+}
+
 #[derive(Clone, Builder)]
 #[builder(setter(strip_option))]
 pub struct Options {
@@ -63,14 +103,46 @@ pub struct Options {
     pub max_randomness_offset: Option<f32>,
     #[builder(default = "Some(1.0)")]
     pub roughness: Option<f32>,
+    /// Turns off all random offsets so shapes come out with their exact, clean geometry.
+    /// Useful as a baseline for golden tests.
+    #[builder(default = "Some(false)")]
+    pub deterministic_preview: Option<bool>,
+    /// Adds fine, seeded wobbles along straight strokes on top of their usual bow, like a
+    /// line drawn with a pencil. Fills are not affected.
+    #[builder(default = "Some(false)")]
+    pub pencil_texture: Option<bool>,
+    /// Ramps the roughness of paths linearly from the first value at their start to the
+    /// second value at their end, by arc length. Overrides `roughness` when set.
+    #[builder(default = "None")]
+    pub roughness_ramp: Option<(f32, f32)>,
     #[builder(default = "Some(2.0)")]
     pub bowing: Option<f32>,
+    /// Overrides `roughness` for pattern fills only, so the fill can be looser or
+    /// crisper than the outline. `None` uses `roughness`.
+    #[builder(default = "None")]
+    pub fill_roughness: Option<f32>,
+    /// Draws fills with no jitter at all, on the exact shape, while the outline stays rough.
+    /// Takes precedence over `fill_roughness`.
+    #[builder(default = "Some(false)")]
+    pub clean_fill: Option<bool>,
+    /// Caps the bowing displacement of a line to this fraction of its length, so short
+    /// segments do not bulge. `None` leaves bowing uncapped.
+    #[builder(default = "None")]
+    pub max_bowing_ratio: Option<f32>,
+    /// Splits lines longer than this into several roughened pieces drawn as one continuous
+    /// stroke, so long lines wobble more than once. `None` draws every line in one piece.
+    #[builder(default = "None")]
+    pub max_segment_length: Option<f32>,
     #[builder(default = "Some(Srgba::new(0.0, 0.0, 0.0, 1.0))")]
     pub stroke: Option<Srgba>,
     #[builder(default = "Some(1.0)")]
     pub stroke_width: Option<f32>,
     #[builder(default = "Some(0.95)")]
     pub curve_fitting: Option<f32>,
+    /// Keeps ellipses at exactly the requested radii instead of randomizing them
+    /// by `curve_fitting`. The stroke itself stays rough.
+    #[builder(default = "Some(false)")]
+    pub perfect_ellipse: Option<bool>,
     #[builder(default = "Some(0.0)")]
     pub curve_tightness: Option<f32>,
     #[builder(default = "Some(9.0)")]
@@ -83,18 +155,80 @@ pub struct Options {
     pub fill_weight: Option<f32>,
     #[builder(default = "Some(-41.0)")]
     pub hachure_angle: Option<f32>,
+    /// Orients hachure lines across each fill polygon's principal axis, found from the
+    /// covariance of its vertices, instead of using `hachure_angle`.
+    #[builder(default = "Some(false)")]
+    pub auto_hachure_angle: Option<bool>,
     #[builder(default = "Some(-1.0)")]
     pub hachure_gap: Option<f32>,
+    /// Places hachure scan lines on a fixed grid whose phase is derived from `seed`, so the
+    /// pattern does not crawl when the shape moves or is re-rendered. When off, scan lines
+    /// start at the top of each fill like rough.js.
+    #[builder(default = "Some(false)")]
+    pub seeded_hachure_phase: Option<bool>,
+    /// Emits hachure lines as thin filled quads of `fill_weight` width in a
+    /// [`OpSetType::FillPath`] instead of strokes in a [`OpSetType::FillSketch`].
+    #[builder(default = "Some(false)")]
+    pub hachure_as_fill: Option<bool>,
+    /// Randomly extends or shortens both ends of every hachure line by up to this
+    /// distance, so fills do not stop exactly at the outline.
+    #[builder(default = "None")]
+    pub fill_end_jitter: Option<f32>,
+    /// Extends both ends of every hachure line by this distance past the outline, for a
+    /// looser look.
+    #[builder(default = "Some(0.0)")]
+    pub hachure_overdraw: Option<f32>,
+    /// Joins hachure segments that lie on the same scan line and touch end to start into
+    /// a single segment, which saves pen lifts on plotters.
+    #[builder(default = "Some(false)")]
+    pub merge_hachure_segments: Option<bool>,
+    /// Shifts the second line set of the cross-hatch fill style across its lines by this
+    /// distance, so the two sets don't always cross at the same grid points.
+    #[builder(default = "None")]
+    pub cross_hatch_phase_offset: Option<f32>,
+    /// Simplifies fill polygons with this tolerance before filling them, which speeds up
+    /// pattern fills of very detailed outlines.
+    #[builder(default = "None")]
+    pub fill_simplify_tolerance: Option<f32>,
     #[builder(default = "Some(1.0)")]
     pub simplification: Option<f32>,
+    /// Fills each subpath of a path on its own instead of filling all subpaths together as
+    /// one even-odd group, where inner subpaths become holes.
+    #[builder(default = "Some(false)")]
+    pub fill_subpaths_separately: Option<bool>,
+    /// Fills open subpaths of a path as if closed by a straight line back to their start.
+    /// When off, only subpaths that end where they started, such as those closed with `Z`,
+    /// are filled.
+    #[builder(default = "Some(true)")]
+    pub fill_open_path_close: Option<bool>,
+    /// Hachures the polygons of a fill as their union, so overlapping areas are filled once
+    /// rather than cancelling out under the even-odd rule.
+    #[builder(default = "Some(false)")]
+    pub fill_union: Option<bool>,
     #[builder(default = "Some(-1.0)")]
     pub dash_offset: Option<f32>,
     #[builder(default = "Some(-1.0)")]
     pub dash_gap: Option<f32>,
     #[builder(default = "Some(-1.0)")]
     pub zigzag_offset: Option<f32>,
+    /// Draws the zigzag fill styles as continuous strokes with rounded turns instead of
+    /// separate segments meeting at sharp points.
+    #[builder(default = "Some(false)")]
+    pub zigzag_round: Option<bool>,
+    /// Randomizes the size of each dot drawn by the dots fill style by up to
+    /// this amount in either direction. `None` keeps all dots the same size.
+    #[builder(default = "None")]
+    pub dot_size_jitter: Option<f32>,
+    /// Shape of the dots drawn by the dots fill style, sized by `fill_weight`.
+    #[builder(default = "Some(DotShape::Circle)")]
+    pub dot_shape: Option<DotShape>,
+    #[builder(default = "Some(DotDistribution::Grid)")]
+    pub dot_distribution: Option<DotDistribution>,
     #[builder(default = "Some(345_u64)")]
     pub seed: Option<u64>,
+    /// Position in a looping animation, in `0.0..1.0`, see [`Options::animate_frame`].
+    #[builder(default = "None")]
+    pub animation_phase: Option<f32>,
     #[builder(default = "None")]
     pub stroke_line_dash: Option<Vec<f64>>,
     #[builder(default = "None")]
@@ -111,12 +245,41 @@ pub struct Options {
     pub disable_multi_stroke: Option<bool>,
     #[builder(default = "Some(false)")]
     pub disable_multi_stroke_fill: Option<bool>,
+    /// Draws fills of shapes whose bounding box is smaller than this on both axes with a
+    /// single pass, as if `disable_multi_stroke_fill` were set for them.
+    #[builder(default = "None")]
+    pub adaptive_multi_stroke_fill: Option<f32>,
+    /// Scales how far the second stroke pass of curves, ellipses and arcs strays from
+    /// the first one. `None` keeps the default spacing.
+    #[builder(default = "None")]
+    pub multi_stroke_offset: Option<f32>,
+    /// Blends the jitter of the second stroke pass toward the first one, from 0 (independent
+    /// passes) to 1 (identical passes). `None` keeps the passes independent.
+    #[builder(default = "None")]
+    pub stroke_correlation: Option<f32>,
     #[builder(default = "Some(false)")]
     pub preserve_vertices: Option<bool>,
+    /// Draws zero size rectangles and ellipses and zero length lines as a single jittered
+    /// dot instead of nothing.
+    #[builder(default = "Some(false)")]
+    pub zero_size_dot: Option<bool>,
+    /// Draws the second pass of a curve as a perpendicular offset of the first pass
+    /// instead of an independently jittered copy.
+    #[builder(default = "Some(false)")]
+    pub parallel_offset_strokes: Option<bool>,
+    /// Redraws each stroke pass of exported ellipses and circles with this many cubic
+    /// segments through points of the rough outline, which keeps svg output small. The
+    /// drawable itself is not changed.
+    #[builder(default = "None")]
+    pub ellipse_export_segments: Option<u32>,
     #[builder(default = "None")]
     pub fixed_decimal_place_digits: Option<f32>,
     #[builder(default = "None")]
     pub randomizer: Option<StdRng>,
+    /// Tapers strokes to a point at both ends. Values are fractions of the total stroke
+    /// length over which width ramps from zero at start and back to zero at end.
+    #[builder(default = "None")]
+    pub taper: Option<(f32, f32)>,
 }
 
 impl Default for Options {
@@ -124,39 +287,100 @@ impl Default for Options {
         Options {
             max_randomness_offset: Some(2.0),
             roughness: Some(1.0),
+            deterministic_preview: Some(false),
+            pencil_texture: Some(false),
+            roughness_ramp: None,
             bowing: Some(2.0),
+            fill_roughness: None,
+            clean_fill: Some(false),
+            max_bowing_ratio: None,
+            max_segment_length: None,
             stroke: Some(Srgba::new(0.0, 0.0, 0.0, 1.0)),
             stroke_width: Some(1.0),
             curve_tightness: Some(0.0),
             curve_fitting: Some(0.95),
+            perfect_ellipse: Some(false),
             curve_step_count: Some(9.0),
             fill: None,
             fill_style: None,
             fill_weight: Some(-1.0),
             hachure_angle: Some(-41.0),
+            auto_hachure_angle: Some(false),
             hachure_gap: Some(-1.0),
+            seeded_hachure_phase: Some(false),
+            hachure_as_fill: Some(false),
+            fill_end_jitter: None,
+            hachure_overdraw: Some(0.0),
+            merge_hachure_segments: Some(false),
+            cross_hatch_phase_offset: None,
+            fill_simplify_tolerance: None,
             dash_offset: Some(-1.0),
             dash_gap: Some(-1.0),
+            zigzag_round: Some(false),
             zigzag_offset: Some(-1.0),
+            dot_size_jitter: None,
+            dot_shape: Some(DotShape::Circle),
+            dot_distribution: Some(DotDistribution::Grid),
             seed: Some(345_u64),
+            animation_phase: None,
             disable_multi_stroke: Some(false),
             disable_multi_stroke_fill: Some(false),
+            adaptive_multi_stroke_fill: None,
+            multi_stroke_offset: None,
+            stroke_correlation: None,
             preserve_vertices: Some(false),
+            zero_size_dot: Some(false),
+            parallel_offset_strokes: Some(false),
             simplification: Some(1.0),
+            fill_subpaths_separately: Some(false),
+            fill_open_path_close: Some(true),
+            fill_union: Some(false),
             stroke_line_dash: None,
             stroke_line_dash_offset: None,
             line_cap: None,
             line_join: None,
             fill_line_dash: None,
             fill_line_dash_offset: None,
+            ellipse_export_segments: None,
             fixed_decimal_place_digits: None,
             randomizer: None,
+            taper: None,
         }
     }
 }
 
+impl OptionsBuilder {
+    /// Sets a dash of length `on` followed by a gap of length `off`, starting `offset` into
+    /// the pattern. Configures both the stroke dash array and the dashed fill style.
+    pub fn dash_pattern(&mut self, on: f32, off: f32, offset: f32) -> &mut Self {
+        self.stroke_line_dash(vec![on as f64, off as f64])
+            .stroke_line_dash_offset(offset as f64)
+            .dash_offset(on)
+            .dash_gap(off)
+    }
+}
+
 impl Options {
+    /// Next value of the random stream, in `0.0..1.0`. While animating, values mid loop
+    /// can stray up to about 0.2 outside that range.
     pub fn random(&mut self) -> f64 {
+        let value = self.next_random();
+        match self.animation_phase {
+            // rotate from one centred value to a second one and back once per loop, so the
+            // jitter moves continuously, keeps its spread mid loop, and `t = 1.0` lands where
+            // `t = 0.0` started
+            Some(t) => {
+                let other = self.next_random();
+                let weight = (1.0 - (f64::from(t) * std::f64::consts::TAU).cos()) / 2.0;
+                let angle = weight * std::f64::consts::FRAC_PI_2;
+                0.5 + (value - 0.5) * angle.cos() + (other - 0.5) * angle.sin()
+            }
+            None => value,
+        }
+    }
+
+    /// Next raw value of the random stream, without the `animation_phase` blend.
+    pub(crate) fn next_random(&mut self) -> f64 {
         match &mut self.randomizer {
             Some(r) => r.gen(),
             None => match self.seed {
@@ -172,6 +396,16 @@ impl Options {
         }
     }
 
+    /// Selects the jitter for a looping animation at phase `t`, where one loop spans
+    /// `0.0..1.0`. Every random offset eases from one seeded value to another and back as
+    /// `t` goes round, so nearby phases draw nearby sketches and `t` and `t + 1.0` draw the
+    /// same geometry. Needs a fixed `seed` to be repeatable across frames.
+    pub fn animate_frame(&mut self, t: f32) -> &mut Self {
+        self.animation_phase = Some(t.rem_euclid(1.0));
+        self.randomizer = None;
+        self
+    }
+
     pub fn set_hachure_angle(&mut self, angle: Option<f32>) -> &mut Self {
         self.hachure_angle = angle;
         self
@@ -181,6 +415,44 @@ impl Options {
         self.hachure_gap = gap;
         self
     }
+
+    /// Returns `stroke_line_dash` as an even length on/off pattern that renderers can cycle
+    /// through. Odd length patterns are repeated once, as in svg. Returns `None` for empty,
+    /// negative or all zero patterns, which draw a solid stroke.
+    pub fn stroke_dash_pattern(&self) -> Option<Vec<f64>> {
+        let dash = self.stroke_line_dash.as_ref()?;
+        if dash.iter().any(|d| *d < 0.0) || dash.iter().sum::<f64>() <= 0.0 {
+            return None;
+        }
+        let mut pattern = dash.clone();
+        if pattern.len() % 2 == 1 {
+            pattern.extend_from_slice(dash);
+        }
+        Some(pattern)
+    }
+
+    /// Whether the fill style already splits its lines by `fill_line_dash`, in which case
+    /// renderers must not dash fill sketches again.
+    pub fn fill_dashed_by_filler(&self) -> bool {
+        self.fill_line_dash.is_some()
+            && matches!(
+                self.fill_style,
+                None | Some(FillStyle::Hachure) | Some(FillStyle::CrossHatch)
+            )
+    }
+
+    /// Whether renderers fill [`OpSetType::FillPath`] sets of a `shape` drawable with the
+    /// even-odd rule. Solid fills are rings that may be concave or overlap, hence even-odd.
+    /// Ellipse fills overlay two passes of the same outline, and `hachure_as_fill` quads
+    /// cross each other, so both need nonzero winding to stay filled.
+    pub fn fill_path_even_odd(&self, shape: &str) -> bool {
+        let hachure_quads = self.hachure_as_fill.unwrap_or(false)
+            && matches!(
+                self.fill_style,
+                None | Some(FillStyle::Hachure) | Some(FillStyle::CrossHatch)
+            );
+        !hachure_quads && !matches!(shape, "ellipse" | "circle" | "arc")
+    }
 }
 
 #[derive(Clone, PartialEq, Debug, Eq)]
@@ -217,6 +489,376 @@ pub struct Drawable<F: Float + Trig> {
     pub sets: Vec<OpSet<F>>,
 }
 
+impl<F: Float + Trig + FromPrimitive> Drawable<F> {
+    /// Returns true when no op set of this drawable has any operations.
+    pub fn is_empty(&self) -> bool {
+        self.sets.iter().all(|set| set.ops.is_empty())
+    }
+
+    /// Returns the number of operations across all op sets.
+    pub fn op_count(&self) -> usize {
+        self.sets.iter().map(|set| set.ops.len()).sum()
+    }
+
+    /// Returns the axis aligned bounds of all operations in this drawable. Curve control
+    /// points are included, so the box may be slightly larger than the rendered shape.
+    pub fn bounds(&self) -> Option<Box2D<F>> {
+        let mut points = self
+            .sets
+            .iter()
+            .flat_map(|set| set.ops.iter())
+            .flat_map(|op| op.data.chunks_exact(2))
+            .map(|xy| Point2D::new(xy[0], xy[1]));
+        let first = points.next()?;
+        Some(points.fold(Box2D::new(first, first), |b, p| {
+            Box2D::new(b.min.min(p), b.max.max(p))
+        }))
+    }
+
+    /// Flattens all stroke op sets into discrete line segments, e.g. for `GL_LINES`
+    /// style rendering. Curves are sampled with the given `tolerance`.
+    pub fn line_segments(&self, tolerance: F) -> Vec<(Point2D<F>, Point2D<F>)>
+    where
+        F: MulAssign + Display,
+    {
+        self.line_segments_with_fills(tolerance, false)
+    }
+
+    /// Same as [`Drawable::line_segments`] but also flattens fill op sets when
+    /// `include_fills` is set.
+    pub fn line_segments_with_fills(
+        &self,
+        tolerance: F,
+        include_fills: bool,
+    ) -> Vec<(Point2D<F>, Point2D<F>)>
+    where
+        F: MulAssign + Display,
+    {
+        self.sets
+            .iter()
+            .filter(|set| include_fills || set.op_set_type == OpSetType::Path)
+            .flat_map(|set| flatten_ops(&set.ops, tolerance))
+            .flat_map(|polyline| {
+                polyline
+                    .windows(2)
+                    .map(|w| (w[0], w[1]))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Exports the stroke op sets as a JSON array of polylines, each an array of `[x, y]`
+    /// pairs. Curves are flattened with `tolerance` and pieces that continue exactly where
+    /// the previous one ended are joined, so closed outlines come out as rings. Needs the
+    /// `json` feature.
+    #[cfg(feature = "json")]
+    pub fn to_json_polylines(&self, tolerance: F) -> String
+    where
+        F: MulAssign + Display,
+    {
+        let mut polylines: Vec<Vec<[f64; 2]>> = vec![];
+        let pieces = self
+            .sets
+            .iter()
+            .filter(|set| set.op_set_type == OpSetType::Path)
+            .flat_map(|set| flatten_ops(&set.ops, tolerance));
+        for piece in pieces {
+            let points = piece
+                .iter()
+                .map(|p| [p.x.to_f64().unwrap_or(0.0), p.y.to_f64().unwrap_or(0.0)]);
+            match polylines.last_mut() {
+                Some(last) if last.last() == points.clone().next().as_ref() => {
+                    last.extend(points.skip(1))
+                }
+                _ => polylines.push(points.collect()),
+            }
+        }
+        serde_json::to_string(&polylines).unwrap_or_default()
+    }
+
+    /// Clips this drawable to `polygon`. Strokes and sketched fills are cut into the
+    /// pieces that lie inside the polygon, which for hachure style fills is the same as
+    /// hatching the intersection. Solid fills are clipped with Sutherland-Hodgman, which
+    /// is exact only for convex clip polygons: against a concave polygon a solid fill can
+    /// keep bridges across its notches.
+    pub fn clip_to_polygon(&self, polygon: &[Point2D<F>]) -> Drawable<F>
+    where
+        F: MulAssign + Display,
+    {
+        let tolerance = _c::<F>(0.1);
+        let sets = self
+            .sets
+            .iter()
+            .map(|set| {
+                let polylines = flatten_ops(&set.ops, tolerance);
+                let ops = if set.op_set_type == OpSetType::FillPath {
+                    polylines
+                        .iter()
+                        .map(|ring| clip_polygon(ring, polygon))
+                        .filter(|ring| ring.len() > 2)
+                        .flat_map(|ring| polyline_ops(&ring))
+                        .collect()
+                } else {
+                    let mut ops = vec![];
+                    let mut last: Option<Point2D<F>> = None;
+                    for (start, end) in polylines.iter().flat_map(|p| {
+                        p.windows(2)
+                            .flat_map(|w| clip_segment_to_polygon(w[0], w[1], polygon))
+                            .collect::<Vec<_>>()
+                    }) {
+                        if last != Some(start) {
+                            ops.push(Op { op: OpType::Move, data: vec![start.x, start.y] });
+                        }
+                        ops.push(Op { op: OpType::LineTo, data: vec![end.x, end.y] });
+                        last = Some(end);
+                    }
+                    ops
+                };
+                OpSet {
+                    op_set_type: set.op_set_type.clone(),
+                    ops,
+                    size: set.size,
+                    path: set.path.clone(),
+                }
+            })
+            .collect();
+        Drawable {
+            shape: self.shape.clone(),
+            options: self.options.clone(),
+            sets,
+        }
+    }
+
+    /// Exports this drawable as a self-contained svg `<g>` element. Strokes are drawn with
+    /// `stroke_width`, sketched fills with `fill_weight` (or half of `stroke_width` when
+    /// unset) and solid fills are filled with the fill color.
+    pub fn to_svg_group(&self, stroke_width: f32) -> String
+    where
+        F: Display,
+    {
+        self.svg_group("<g>", stroke_width)
+    }
+
+    /// Same as [`Drawable::to_svg_group`], but the group carries `m` as its
+    /// `transform="matrix(a b c d e f)"` attribute. Path data is left untransformed.
+    pub fn to_svg_group_with_transform(&self, stroke_width: f32, m: [f64; 6]) -> String
+    where
+        F: Display,
+    {
+        let open = format!(
+            r#"<g transform="matrix({} {} {} {} {} {})">"#,
+            m[0], m[1], m[2], m[3], m[4], m[5]
+        );
+        self.svg_group(&open, stroke_width)
+    }
+
+    fn svg_group(&self, open: &str, stroke_width: f32) -> String
+    where
+        F: Display,
+    {
+        let o = &self.options;
+        let fill_weight = match o.fill_weight {
+            Some(w) if w >= 0.0 => w,
+            _ => stroke_width / 2.0,
+        };
+        let mut group = String::from(open);
+        for set in self.sets.iter() {
+            let d = Generator::ops_to_path(export_opset(&self.shape, set, o), None);
+            let d = d.trim_end();
+            let element = match set.op_set_type {
+                OpSetType::Path => o.stroke.map(|stroke| {
+                    format!(
+                        r#"<path d="{}" fill="none" stroke="{}" stroke-width="{}"/>"#,
+                        d,
+                        svg_color(stroke),
+                        stroke_width
+                    )
+                }),
+                OpSetType::FillPath => o.fill.map(|fill| {
+                    format!(
+                        r#"<path d="{}" fill="{}" stroke="none"/>"#,
+                        d,
+                        svg_color(fill)
+                    )
+                }),
+                OpSetType::FillSketch => o.fill.map(|fill| {
+                    format!(
+                        r#"<path d="{}" fill="none" stroke="{}" stroke-width="{}"/>"#,
+                        d,
+                        svg_color(fill),
+                        fill_weight
+                    )
+                }),
+            };
+            group.extend(element);
+        }
+        group.push_str("</g>");
+        group
+    }
+
+    /// Returns a copy of this drawable with every coordinate rounded to the nearest
+    /// multiple of `step`.
+    pub fn snap_to_grid(&self, step: F) -> Drawable<F> {
+        let sets = self
+            .sets
+            .iter()
+            .map(|set| OpSet {
+                op_set_type: set.op_set_type.clone(),
+                ops: set
+                    .ops
+                    .iter()
+                    .map(|op| Op {
+                        op: op.op.clone(),
+                        data: op.data.iter().map(|v| (*v / step).round() * step).collect(),
+                    })
+                    .collect(),
+                size: set.size,
+                path: set.path.clone(),
+            })
+            .collect();
+        Drawable {
+            shape: self.shape.clone(),
+            options: self.options.clone(),
+            sets,
+        }
+    }
+
+    /// Returns a copy of this drawable scaled and moved so its [`Drawable::bounds`] fill a
+    /// `width` by `height` box at the origin. With `preserve_aspect` both axes use the
+    /// smaller scale, so the result fits the box without being distorted.
+    pub fn scale_to_fit(&self, width: F, height: F, preserve_aspect: bool) -> Drawable<F> {
+        let bounds = self.bounds().unwrap_or_else(Box2D::zero);
+        let size = bounds.size();
+        let scale_of = |target: F, extent: F| {
+            if extent > F::zero() {
+                Some(target / extent)
+            } else {
+                None
+            }
+        };
+        let (sx, sy) = match (scale_of(width, size.width), scale_of(height, size.height)) {
+            (Some(sx), Some(sy)) if preserve_aspect => (sx.min(sy), sx.min(sy)),
+            (Some(sx), Some(sy)) => (sx, sy),
+            (Some(s), None) | (None, Some(s)) => (s, s),
+            (None, None) => (F::one(), F::one()),
+        };
+        let sets = self
+            .sets
+            .iter()
+            .map(|set| OpSet {
+                op_set_type: set.op_set_type.clone(),
+                ops: set
+                    .ops
+                    .iter()
+                    .map(|op| Op {
+                        op: op.op.clone(),
+                        data: op
+                            .data
+                            .chunks_exact(2)
+                            .flat_map(|xy| {
+                                [(xy[0] - bounds.min.x) * sx, (xy[1] - bounds.min.y) * sy]
+                            })
+                            .collect(),
+                    })
+                    .collect(),
+                size: set.size,
+                path: set.path.clone(),
+            })
+            .collect();
+        Drawable {
+            shape: self.shape.clone(),
+            options: self.options.clone(),
+            sets,
+        }
+    }
+
+    /// Returns a rough rectangle around [`Drawable::bounds`], useful to debug layout.
+    /// The result is empty when this drawable has no operations.
+    pub fn debug_bbox_drawable(&self, options: &Option<Options>) -> Drawable<F> {
+        let mut options = options.clone().unwrap_or_else(|| self.options.clone());
+        let sets = match self.bounds() {
+            Some(b) => vec![crate::renderer::rectangle(
+                b.min.x,
+                b.min.y,
+                b.width(),
+                b.height(),
+                &mut options,
+            )],
+            None => vec![],
+        };
+        Drawable { shape: "rectangle".into(), options, sets }
+    }
+}
+
+/// Flattens ops into polylines, starting a new one at every move.
+fn flatten_ops<F>(ops: &[Op<F>], tolerance: F) -> Vec<Vec<Point2D<F>>>
+where
+    F: Float + Trig + FromPrimitive + MulAssign + Display,
+{
+    let mut polylines: Vec<Vec<Point2D<F>>> = vec![];
+    let mut current = Point2D::new(F::zero(), F::zero());
+    for op in ops.iter() {
+        match op.op {
+            OpType::Move => {
+                current = Point2D::new(op.data[0], op.data[1]);
+                polylines.push(vec![current]);
+            }
+            OpType::LineTo => {
+                current = Point2D::new(op.data[0], op.data[1]);
+                push_point(&mut polylines, current);
+            }
+            OpType::BCurveTo => {
+                let start = current;
+                current = Point2D::new(op.data[4], op.data[5]);
+                let curve = [
+                    start,
+                    Point2D::new(op.data[0], op.data[1]),
+                    Point2D::new(op.data[2], op.data[3]),
+                    current,
+                ];
+                if polylines.is_empty() {
+                    polylines.push(vec![start]);
+                }
+                for p in points_on_bezier_curves(&curve, tolerance, None) {
+                    push_point(&mut polylines, p);
+                }
+                push_point(&mut polylines, current);
+            }
+        }
+    }
+    polylines
+}
+
+fn svg_color(color: Srgba) -> String {
+    let (r, g, b, a): (u8, u8, u8, f32) = {
+        let c = color.into_format::<u8, f32>();
+        (c.red, c.green, c.blue, c.alpha)
+    };
+    format!("rgba({}, {}, {}, {})", r, g, b, a)
+}
+
+fn push_point<F: Float>(polylines: &mut Vec<Vec<Point2D<F>>>, p: Point2D<F>) {
+    match polylines.last_mut() {
+        Some(polyline) => {
+            if polyline.last() != Some(&p) {
+                polyline.push(p);
+            }
+        }
+        None => polylines.push(vec![p]),
+    }
+}
+
+fn polyline_ops<F: Float + Trig>(points: &[Point2D<F>]) -> Vec<Op<F>> {
+    points
+        .iter()
+        .enumerate()
+        .map(|(i, p)| Op {
+            op: if i == 0 { OpType::Move } else { OpType::LineTo },
+            data: vec![p.x, p.y],
+        })
+        .collect()
+}
+
 pub struct PathInfo {
     pub d: String,
     pub stroke: Option<Srgba>,
@@ -0,0 +1,43 @@
+use std::fmt;
+
+use num_traits::Float;
+
+/// Errors reported by the fallible shape methods of [`crate::generator::Generator`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoughrError {
+    /// A coordinate or size was NaN or infinite.
+    NonFiniteInput,
+    /// A width, height or diameter was negative.
+    NegativeSize,
+    /// A points list was not an even number of coordinates.
+    InvalidPoints,
+}
+
+impl fmt::Display for RoughrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RoughrError::NonFiniteInput => write!(f, "shape input is not a finite number"),
+            RoughrError::NegativeSize => write!(f, "shape size is negative"),
+            RoughrError::InvalidPoints => write!(f, "points list is malformed"),
+        }
+    }
+}
+
+impl std::error::Error for RoughrError {}
+
+pub(crate) fn check_finite<F: Float>(values: &[F]) -> Result<(), RoughrError> {
+    if values.iter().all(|v| v.is_finite()) {
+        Ok(())
+    } else {
+        Err(RoughrError::NonFiniteInput)
+    }
+}
+
+pub(crate) fn check_size<F: Float>(values: &[F]) -> Result<(), RoughrError> {
+    check_finite(values)?;
+    if values.iter().any(|v| *v < F::zero()) {
+        Err(RoughrError::NegativeSize)
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+use std::borrow::BorrowMut;
+use std::marker::PhantomData;
+
+use euclid::default::Point2D;
+use euclid::Trig;
+use num_traits::{Float, FromPrimitive};
+
+use super::traits::PatternFiller;
+use crate::core::{Op, OpSet, OpSetType, Options, _c};
+use crate::geometry::offset_polygon;
+use crate::renderer::_double_line;
+
+pub struct ConcentricFiller<F> {
+    _phantom: PhantomData<F>,
+}
+
+impl<F, P> PatternFiller<F, P> for ConcentricFiller<F>
+where
+    F: Float + Trig + FromPrimitive,
+    P: BorrowMut<Vec<Vec<Point2D<F>>>>,
+{
+    fn fill_polygons(&self, mut polygon_list: P, o: &mut Options) -> crate::core::OpSet<F> {
+        let mut gap = o.hachure_gap.map(_c::<F>).unwrap_or_else(|| _c::<F>(-1.0));
+        if gap < F::zero() {
+            gap = o.stroke_width.map(_c::<F>).unwrap_or_else(|| _c::<F>(1.0)) * _c::<F>(4.0);
+        }
+        gap = gap.max(_c::<F>(0.1));
+
+        let mut ops = vec![];
+        for polygon in polygon_list.borrow_mut().iter() {
+            for ring in ConcentricFiller::rings(polygon, gap) {
+                ops.extend(ConcentricFiller::render_ring(&ring, o));
+            }
+        }
+        OpSet {
+            op_set_type: OpSetType::FillSketch,
+            ops,
+            size: None,
+            path: None,
+        }
+    }
+}
+
+impl<F: Float + Trig + FromPrimitive> ConcentricFiller<F> {
+    pub fn new() -> Self {
+        ConcentricFiller { _phantom: PhantomData }
+    }
+
+    /// Nested copies of `polygon`, each inset by `gap` from the previous one, until the
+    /// polygon collapses.
+    fn rings(polygon: &[Point2D<F>], gap: F) -> Vec<Vec<Point2D<F>>> {
+        let mut rings = vec![];
+        let mut current = offset_polygon(polygon, gap);
+        while let Some(ring) = current {
+            current = offset_polygon(&ring, gap);
+            rings.push(ring);
+        }
+        rings
+    }
+
+    fn render_ring(ring: &[Point2D<F>], o: &mut Options) -> Vec<Op<F>> {
+        let mut ops = vec![];
+        for i in 0..ring.len() {
+            let start = ring[i];
+            let end = ring[(i + 1) % ring.len()];
+            ops.extend(_double_line(start.x, start.y, end.x, end.y, o, true));
+        }
+        ops
+    }
+}
+
+impl<F: Float + Trig + FromPrimitive> Default for ConcentricFiller<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use euclid::point2;
+
+    use super::ConcentricFiller;
+
+    #[test]
+    fn square_produces_shrinking_rings() {
+        let square = vec![
+            point2(0.0f32, 0.0),
+            point2(100.0, 0.0),
+            point2(100.0, 100.0),
+            point2(0.0, 100.0),
+        ];
+        let rings = ConcentricFiller::rings(&square, 10.0);
+        assert_eq!(rings.len(), 4);
+        let widths: Vec<f32> = rings.iter().map(|r| r[1].x - r[0].x).collect();
+        assert!(widths.windows(2).all(|w| w[1] < w[0]));
+        assert!(widths[0] < 100.0);
+    }
+}
@@ -7,9 +7,9 @@ use num_traits::{Float, FromPrimitive};
 
 use super::scan_line_hachure::polygon_hachure_lines;
 use super::traits::PatternFiller;
-use crate::core::{OpSet, Options, _c, _cc};
-use crate::geometry::Line;
-use crate::renderer::ellipse;
+use crate::core::{DotDistribution, DotShape, OpSet, Options, _c, _cc};
+use crate::geometry::{point_in_polygon, Line};
+use crate::renderer::{ellipse, line, linear_path};
 
 pub struct DotFiller<F> {
     _phantom: PhantomData<F>,
@@ -21,9 +21,13 @@ where
     P: BorrowMut<Vec<Vec<Point2D<F>>>>,
 {
     fn fill_polygons(&self, mut polygon_list: P, o: &mut Options) -> crate::core::OpSet<F> {
-        o.set_hachure_angle(Some(0.0));
-        let lines = polygon_hachure_lines(polygon_list.borrow_mut(), o);
-        let ops = DotFiller::dots_on_line(lines, o);
+        let ops = if o.dot_distribution == Some(DotDistribution::Poisson) {
+            DotFiller::poisson_dots(polygon_list.borrow(), o)
+        } else {
+            o.set_hachure_angle(Some(0.0));
+            let lines = polygon_hachure_lines(polygon_list.borrow_mut(), o);
+            DotFiller::dots_on_line(lines, o)
+        };
         OpSet {
             op_set_type: crate::core::OpSetType::FillSketch,
             ops,
@@ -39,15 +43,7 @@ impl<F: Float + Trig + FromPrimitive> DotFiller<F> {
 
     fn dots_on_line(lines: Vec<Line<F>>, o: &mut Options) -> Vec<crate::core::Op<F>> {
         let mut ops = vec![];
-        let mut gap = o.hachure_gap.map(_c::<F>).unwrap_or_else(|| _c::<F>(-1.0));
-        if gap < F::zero() {
-            gap = o.stroke_width.map(_c::<F>).unwrap_or_else(|| _c::<F>(1.0)) * _c::<F>(4.0);
-        }
-        gap = gap.max(_c::<F>(0.1));
-        let mut fweight = o.fill_weight.map(_c::<F>).unwrap_or_else(|| _c::<F>(-1.0));
-        if fweight < F::zero() {
-            fweight = o.stroke_width.map(_c::<F>).unwrap_or_else(|| _c::<F>(1.0)) / _c::<F>(2.0);
-        }
+        let (gap, fweight) = DotFiller::gap_and_weight(o);
 
         let ro = gap / _c::<F>(4.0);
         for line in lines.iter() {
@@ -64,13 +60,170 @@ impl<F: Float + Trig + FromPrimitive> DotFiller<F> {
                 let y = min_y + offset + (F::from(i).unwrap() * gap);
                 let cx = (x - ro) + _cc::<F>(o.random()) * _c::<F>(2.0) * ro;
                 let cy = (y - ro) + _cc::<F>(o.random()) * _c::<F>(2.0) * ro;
-                let ellipse_ops = ellipse(cx, cy, fweight, fweight, o);
-                ops.extend(ellipse_ops.ops);
+                let size = DotFiller::dot_size(fweight, o);
+                ops.extend(DotFiller::dot(cx, cy, size, o).ops);
             }
         }
 
         ops
     }
+
+    fn poisson_dots(polygon_list: &[Vec<Point2D<F>>], o: &mut Options) -> Vec<crate::core::Op<F>> {
+        let (gap, fweight) = DotFiller::gap_and_weight(o);
+        let mut ops = vec![];
+        for p in DotFiller::poisson_disk_points(polygon_list, gap, o) {
+            let size = DotFiller::dot_size(fweight, o);
+            ops.extend(DotFiller::dot(p.x, p.y, size, o).ops);
+        }
+        ops
+    }
+
+    /// Bridson's Poisson-disk sampling over the even-odd interior of `polygon_list`, keeping
+    /// points at least `radius` apart. Uses the options' randomizer so seeded fills repeat.
+    fn poisson_disk_points(
+        polygon_list: &[Vec<Point2D<F>>],
+        radius: F,
+        o: &mut Options,
+    ) -> Vec<Point2D<F>> {
+        const CANDIDATES: usize = 30;
+        let vertices = polygon_list.iter().flatten();
+        let (min, max) = match vertices.clone().next() {
+            Some(first) => {
+                vertices.fold((*first, *first), |(min, max), p| (min.min(*p), max.max(*p)))
+            }
+            None => return vec![],
+        };
+        let inside = |p: Point2D<F>| {
+            polygon_list
+                .iter()
+                .filter(|polygon| point_in_polygon(p, polygon))
+                .count()
+                % 2
+                == 1
+        };
+        let cell = radius / _c::<F>(std::f32::consts::SQRT_2);
+        let columns = ((max.x - min.x) / cell).ceil().to_usize().unwrap_or(0) + 1;
+        let rows = ((max.y - min.y) / cell).ceil().to_usize().unwrap_or(0) + 1;
+        let mut grid: Vec<Option<usize>> = vec![None; columns * rows];
+        let cell_of = |p: Point2D<F>| {
+            let column = ((p.x - min.x) / cell)
+                .to_usize()
+                .unwrap_or(0)
+                .min(columns - 1);
+            let row = ((p.y - min.y) / cell).to_usize().unwrap_or(0).min(rows - 1);
+            (column, row)
+        };
+        let mut points: Vec<Point2D<F>> = vec![];
+        let mut active: Vec<usize> = vec![];
+        let fits = |p: Point2D<F>, points: &[Point2D<F>], grid: &[Option<usize>]| {
+            if p.x < min.x || p.y < min.y || p.x > max.x || p.y > max.y || !inside(p) {
+                return false;
+            }
+            let (column, row) = cell_of(p);
+            for r in row.saturating_sub(2)..(row + 3).min(rows) {
+                for c in column.saturating_sub(2)..(column + 3).min(columns) {
+                    if let Some(i) = grid[r * columns + c] {
+                        if (points[i] - p).length() < radius {
+                            return false;
+                        }
+                    }
+                }
+            }
+            true
+        };
+        let mut misses = 0;
+        // keep seeding new regions until random seeds stop finding room
+        while misses < CANDIDATES {
+            let seed = Point2D::new(
+                min.x + _cc::<F>(o.random()) * (max.x - min.x),
+                min.y + _cc::<F>(o.random()) * (max.y - min.y),
+            );
+            if !fits(seed, &points, &grid) {
+                misses += 1;
+                continue;
+            }
+            let (column, row) = cell_of(seed);
+            grid[row * columns + column] = Some(points.len());
+            active.push(points.len());
+            points.push(seed);
+            while !active.is_empty() {
+                let pick = (_cc::<F>(o.random()) * F::from_usize(active.len()).unwrap())
+                    .to_usize()
+                    .unwrap_or(0)
+                    .min(active.len() - 1);
+                let center = points[active[pick]];
+                let mut found = false;
+                for _ in 0..CANDIDATES {
+                    let angle = _cc::<F>(o.random()) * _c::<F>(std::f32::consts::TAU);
+                    let distance = radius * (F::one() + _cc::<F>(o.random()));
+                    let candidate = Point2D::new(
+                        center.x + distance * Float::cos(angle),
+                        center.y + distance * Float::sin(angle),
+                    );
+                    if fits(candidate, &points, &grid) {
+                        let (column, row) = cell_of(candidate);
+                        grid[row * columns + column] = Some(points.len());
+                        active.push(points.len());
+                        points.push(candidate);
+                        found = true;
+                        break;
+                    }
+                }
+                if !found {
+                    active.swap_remove(pick);
+                }
+            }
+        }
+        points
+    }
+
+    fn dot(cx: F, cy: F, size: F, o: &mut Options) -> OpSet<F> {
+        let half = size / _c::<F>(2.0);
+        match o.dot_shape.unwrap_or_default() {
+            DotShape::Circle => ellipse(cx, cy, size, size, o),
+            DotShape::Square => linear_path(
+                &[
+                    Point2D::new(cx - half, cy - half),
+                    Point2D::new(cx + half, cy - half),
+                    Point2D::new(cx + half, cy + half),
+                    Point2D::new(cx - half, cy + half),
+                ],
+                true,
+                o,
+            ),
+            DotShape::Cross => {
+                let mut cross = line(cx - half, cy - half, cx + half, cy + half, o);
+                let other = line(cx - half, cy + half, cx + half, cy - half, o);
+                cross.ops.extend(other.ops);
+                cross
+            }
+        }
+    }
+
+    /// Dot spacing from `hachure_gap` and dot size from `fill_weight`, both falling back to
+    /// multiples of `stroke_width` when negative.
+    fn gap_and_weight(o: &Options) -> (F, F) {
+        let mut gap = o.hachure_gap.map(_c::<F>).unwrap_or_else(|| _c::<F>(-1.0));
+        if gap < F::zero() {
+            gap = o.stroke_width.map(_c::<F>).unwrap_or_else(|| _c::<F>(1.0)) * _c::<F>(4.0);
+        }
+        gap = gap.max(_c::<F>(0.1));
+        let mut fweight = o.fill_weight.map(_c::<F>).unwrap_or_else(|| _c::<F>(-1.0));
+        if fweight < F::zero() {
+            fweight = o.stroke_width.map(_c::<F>).unwrap_or_else(|| _c::<F>(1.0)) / _c::<F>(2.0);
+        }
+        (gap, fweight)
+    }
+
+    fn dot_size(fweight: F, o: &mut Options) -> F {
+        match o.dot_size_jitter {
+            Some(jitter) => {
+                let offset = (_cc::<F>(o.random()) * _c::<F>(2.0) - F::one()) * _c::<F>(jitter);
+                (fweight + offset).max(_c::<F>(0.1))
+            }
+            None => fweight,
+        }
+    }
 }
 
 impl<F: Float + Trig + FromPrimitive> Default for DotFiller<F> {
@@ -78,3 +231,88 @@ impl<F: Float + Trig + FromPrimitive> Default for DotFiller<F> {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use euclid::point2;
+
+    use super::DotFiller;
+    use crate::core::{DotDistribution, DotShape, OpType, OptionsBuilder};
+    use crate::filler::traits::PatternFiller;
+
+    #[test]
+    fn dot_size_jitter_varies_deterministically() {
+        let sizes = || {
+            let mut o = OptionsBuilder::default()
+                .dot_size_jitter(1.0)
+                .build()
+                .unwrap();
+            (0..8)
+                .map(|_| DotFiller::<f32>::dot_size(2.0, &mut o))
+                .collect::<Vec<_>>()
+        };
+        let first = sizes();
+        assert_eq!(first, sizes());
+        assert!(first.iter().all(|s| (1.0..=3.0).contains(s)));
+        assert!(first.iter().any(|s| *s != first[0]));
+
+        let mut uniform = OptionsBuilder::default().build().unwrap();
+        assert_eq!(DotFiller::<f32>::dot_size(2.0, &mut uniform), 2.0);
+    }
+
+    #[test]
+    fn dot_shape_changes_dot_ops() {
+        let moves_per_shape = |shape| {
+            let square = vec![vec![
+                point2(0.0f32, 0.0),
+                point2(40.0, 0.0),
+                point2(40.0, 40.0),
+                point2(0.0, 40.0),
+            ]];
+            let mut o = OptionsBuilder::default()
+                .dot_shape(shape)
+                .disable_multi_stroke(true)
+                .hachure_gap(8.0)
+                .build()
+                .unwrap();
+            DotFiller::new()
+                .fill_polygons(square, &mut o)
+                .ops
+                .iter()
+                .filter(|op| op.op == OpType::Move)
+                .count()
+        };
+        let dots = moves_per_shape(DotShape::Circle);
+        assert!(dots > 0);
+        assert_eq!(moves_per_shape(DotShape::Square), dots * 4);
+        assert_eq!(moves_per_shape(DotShape::Cross), dots * 2);
+    }
+
+    #[test]
+    fn poisson_dots_are_seeded_and_spaced() {
+        let square = vec![vec![
+            point2(0.0f32, 0.0),
+            point2(40.0, 0.0),
+            point2(40.0, 40.0),
+            point2(0.0, 40.0),
+        ]];
+        let sample = || {
+            let mut o = OptionsBuilder::default()
+                .seed(5_u64)
+                .hachure_gap(6.0)
+                .dot_distribution(DotDistribution::Poisson)
+                .build()
+                .unwrap();
+            DotFiller::<f32>::poisson_disk_points(&square, 6.0, &mut o)
+        };
+        let points = sample();
+        assert!(points.len() > 10);
+        assert_eq!(points, sample());
+        for (i, a) in points.iter().enumerate() {
+            assert!(a.x >= 0.0 && a.x <= 40.0 && a.y >= 0.0 && a.y <= 40.0);
+            for b in points.iter().skip(i + 1) {
+                assert!((*a - *b).length() >= 6.0);
+            }
+        }
+    }
+}
@@ -7,7 +7,7 @@ use num_traits::{Float, FromPrimitive};
 
 use super::scan_line_hachure::ScanlineHachureFiller;
 use super::traits::PatternFiller;
-use crate::core::Options;
+use crate::core::{Options, _c};
 
 pub struct HatchFiller<F> {
     _phantom: PhantomData<F>,
@@ -24,7 +24,10 @@ where
             .hachure_filler
             .fill_polygons(polygon_list.borrow_mut(), o);
         o.set_hachure_angle(o.hachure_angle.map(|a| a + 90.0));
-        let set2 = self.hachure_filler.fill_polygons(polygon_list, o);
+        let offset = o.cross_hatch_phase_offset.unwrap_or(0.0);
+        let set2 = self
+            .hachure_filler
+            .fill_polygons_with_offset(polygon_list, o, _c(offset));
         set1.ops.extend(set2.ops);
         set1
     }
@@ -44,3 +47,51 @@ impl<F: Float + Trig + FromPrimitive> Default for HatchFiller<F> {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use euclid::point2;
+
+    use super::HatchFiller;
+    use crate::core::{OpType, OptionsBuilder};
+    use crate::filler::traits::PatternFiller;
+
+    #[test]
+    fn cross_hatch_phase_offset_moves_second_set() {
+        let vertical_lines = |offset: Option<f32>, seed: Option<u64>| {
+            let square = vec![vec![
+                point2(0.0f32, 0.0),
+                point2(40.0, 0.0),
+                point2(40.0, 40.0),
+                point2(0.0, 40.0),
+            ]];
+            let mut builder = OptionsBuilder::default();
+            builder
+                .roughness(0.0)
+                .hachure_angle(-90.0)
+                .hachure_gap(8.0)
+                .disable_multi_stroke_fill(true);
+            if let Some(offset) = offset {
+                builder.cross_hatch_phase_offset(offset);
+            }
+            let mut o = builder.build().unwrap();
+            o.seed = seed;
+            let ops = HatchFiller::new().fill_polygons(square, &mut o).ops;
+            ops.windows(2)
+                .filter(|w| w[0].op == OpType::Move && (w[0].data[0] - w[1].data[4]).abs() < 1e-3)
+                .map(|w| w[0].data[0])
+                .collect::<Vec<f32>>()
+        };
+        for seed in [Some(345), None] {
+            let aligned = vertical_lines(None, seed);
+            let shifted = vertical_lines(Some(2.0), seed);
+
+            assert!(!aligned.is_empty() && !shifted.is_empty());
+            // the second set moves by +2 along x; -2 would show up as a phase of 6
+            for x in shifted.iter() {
+                let phase = (x - aligned[0]).rem_euclid(8.0);
+                assert!((phase - 2.0).abs() < 1e-3, "{}", phase);
+            }
+        }
+    }
+}
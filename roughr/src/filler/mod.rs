@@ -4,6 +4,7 @@ use euclid::default::Point2D;
 use euclid::Trig;
 use num_traits::{Float, FromPrimitive};
 
+use self::concentric_filler::ConcentricFiller;
 use self::dashed_filler::DashedFiller;
 use self::dot_filler::DotFiller;
 use self::hatch_filler::HatchFiller;
@@ -12,6 +13,7 @@ use self::traits::PatternFiller;
 use self::zig_zag_filler::ZigZagFiller;
 use self::zig_zag_line_filler::ZigZagLineFiller;
 
+pub mod concentric_filler;
 pub mod dashed_filler;
 pub mod dot_filler;
 pub mod hatch_filler;
@@ -27,6 +29,7 @@ pub enum FillerType {
     HatchFiller,
     ZigZagFiller,
     ZigZagLineFiller,
+    ConcentricFiller,
 }
 
 pub fn get_filler<'a, F, P>(f: FillerType) -> Box<dyn PatternFiller<F, P> + 'a>
@@ -41,5 +44,6 @@ where
         FillerType::HatchFiller => Box::new(HatchFiller::new()),
         FillerType::ZigZagFiller => Box::new(ZigZagFiller::new()),
         FillerType::ZigZagLineFiller => Box::new(ZigZagLineFiller::new()),
+        FillerType::ConcentricFiller => Box::new(ConcentricFiller::new()),
     }
 }
@@ -2,12 +2,14 @@ use std::borrow::BorrowMut;
 use std::cmp::Ordering;
 use std::marker::PhantomData;
 
-use euclid::default::Point2D;
+use euclid::default::{Point2D, Vector2D};
 use euclid::Trig;
 use num_traits::{Float, FromPrimitive};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
 use super::traits::PatternFiller;
-use crate::core::{OpSet, Options, _c};
+use crate::core::{Op, OpSet, OpType, Options, _c, _cc};
 use crate::geometry::{rotate_lines, rotate_points, Line};
 
 #[derive(Clone)]
@@ -16,6 +18,8 @@ struct EdgeEntry<F: Float + FromPrimitive + Trig> {
     pub(crate) ymax: F,
     pub(crate) x: F,
     pub(crate) islope: F,
+    /// +1 for edges going down in y, -1 for edges going up. Used for union fills.
+    pub(crate) winding: i32,
 }
 
 impl<F: Float + FromPrimitive + Trig> std::fmt::Display for EdgeEntry<F> {
@@ -35,9 +39,26 @@ struct ActiveEdgeEntry<F: Float + FromPrimitive + Trig> {
     pub(crate) edge: EdgeEntry<F>,
 }
 
+impl<F: Float + FromPrimitive + Trig> ActiveEdgeEntry<F> {
+    /// Activates `edge` at scan line `y`, moving its x to where the edge crosses `y`.
+    fn at(y: F, mut edge: EdgeEntry<F>) -> Self {
+        edge.x = edge.x + (y - edge.ymin) * edge.islope;
+        ActiveEdgeEntry { s: y, edge }
+    }
+}
+
 pub fn polygon_hachure_lines<F: Float + FromPrimitive + Trig>(
     polygon_list: &mut Vec<Vec<Point2D<F>>>,
     options: &Options,
+) -> Vec<Line<F>> {
+    polygon_hachure_lines_with_offset(polygon_list, options, F::zero())
+}
+
+/// Same as [`polygon_hachure_lines`] but shifts the scan lines across themselves by `offset`.
+pub(crate) fn polygon_hachure_lines_with_offset<F: Float + FromPrimitive + Trig>(
+    polygon_list: &mut Vec<Vec<Point2D<F>>>,
+    options: &Options,
+    offset: F,
 ) -> Vec<Line<F>> {
     let angle = options.hachure_angle.unwrap_or(0.0) + 90.0;
     let mut gap = options.hachure_gap.unwrap_or(0.0);
@@ -45,7 +66,12 @@ pub fn polygon_hachure_lines<F: Float + FromPrimitive + Trig>(
         gap = options.stroke_width.unwrap_or(0.0) * 4.0;
     }
 
-    gap = f32::max(gap, 0.1);
+    // keep lines apart by at least half their width again, or thick lines merge into a blob
+    let fill_weight = match options.fill_weight {
+        Some(weight) if weight >= 0.0 => weight,
+        _ => options.stroke_width.unwrap_or(1.0) / 2.0,
+    };
+    gap = f32::max(f32::max(gap, fill_weight * 1.5), 0.1);
 
     let center = Point2D::new(_c(0.0), _c(0.0));
     if angle != 0.0 {
@@ -54,7 +80,19 @@ pub fn polygon_hachure_lines<F: Float + FromPrimitive + Trig>(
             .for_each(|polygon| *polygon = rotate_points(polygon, &center, _c(angle)))
     }
 
-    let mut lines = straight_hachure_lines(polygon_list, _c(gap));
+    // derive the scan line phase from the seed alone so the pattern stays anchored to the same
+    // grid across renders instead of starting wherever the polygon happens to start
+    let phase = if options.seeded_hachure_phase.unwrap_or(false) {
+        let seed = options.seed.unwrap_or(0);
+        Some(_c::<F>(StdRng::seed_from_u64(seed).gen::<f32>() * gap))
+    } else {
+        None
+    };
+    let union = options.fill_union.unwrap_or(false);
+    let mut lines = straight_hachure_lines(polygon_list, _c(gap), phase, offset, union);
+    if options.merge_hachure_segments.unwrap_or(false) {
+        lines = merge_touching_lines(lines);
+    }
 
     if angle != 0.0 {
         polygon_list
@@ -66,7 +104,37 @@ pub fn polygon_hachure_lines<F: Float + FromPrimitive + Trig>(
     return lines;
 }
 
-fn straight_hachure_lines<F>(polygon_list: &mut [Vec<Point2D<F>>], gap: F) -> Vec<Line<F>>
+/// Joins horizontal lines on the same scan line where one ends where the next starts.
+/// Expects lines ordered by scan line and then by x, as `straight_hachure_lines` emits them.
+fn merge_touching_lines<F: Float + FromPrimitive + Trig>(lines: Vec<Line<F>>) -> Vec<Line<F>> {
+    let tolerance = _c::<F>(1e-4);
+    let mut merged: Vec<Line<F>> = vec![];
+    for line in lines {
+        if let Some(last) = merged.last_mut() {
+            if last.end_point.y == line.start_point.y
+                && F::abs(last.end_point.x - line.start_point.x) <= tolerance
+            {
+                last.end_point = line.end_point;
+                continue;
+            }
+        }
+        merged.push(line);
+    }
+    merged
+}
+
+/// Computes horizontal hachure lines. When `phase` is given, scan lines are placed at
+/// `phase + k * gap` instead of starting at the top of the polygon. Either start is moved
+/// down by `offset`, wrapped to the gap. With `union`, spans are
+/// taken where any polygon covers the scan line (nonzero rule over polygons oriented the same
+/// way) instead of pairing edges even-odd.
+fn straight_hachure_lines<F>(
+    polygon_list: &mut [Vec<Point2D<F>>],
+    gap: F,
+    phase: Option<F>,
+    offset: F,
+    union: bool,
+) -> Vec<Line<F>>
 where
     F: Float + FromPrimitive + Trig,
 {
@@ -91,6 +159,11 @@ where
     let mut edges: Vec<EdgeEntry<F>> = vec![];
 
     for vertices in vertex_array.iter() {
+        // orient every polygon the same way so their windings add up instead of cancelling
+        let twice_area = vertices.windows(2).fold(F::zero(), |acc, w| {
+            acc + (w[0].x * w[1].y - w[1].x * w[0].y)
+        });
+        let orientation = if twice_area < F::zero() { -1 } else { 1 };
         let mut edge_extension = vertices[..]
             .windows(2)
             .filter_map(|w| {
@@ -103,6 +176,11 @@ where
                         ymax: F::max(p1.y, p2.y),
                         x: if ymin == p1.y { p1.x } else { p2.x },
                         islope: (p2.x - p1.x) / (p2.y - p1.y),
+                        winding: if p2.y > p1.y {
+                            orientation
+                        } else {
+                            -orientation
+                        },
                     })
                 } else {
                     None
@@ -142,6 +220,20 @@ where
 
     let mut active_edges: Vec<ActiveEdgeEntry<F>> = Vec::new();
     let mut y = edges.first().unwrap().ymin;
+    let shifted = phase.is_some() || offset != F::zero();
+    if shifted {
+        let phase = phase.unwrap_or(y) + offset;
+        y = phase + ((y - phase) / gap).ceil() * gap;
+    }
+    // scan lines off the polygon's own grid cross edges below their top, so start those edges
+    // where they cross; otherwise keep rough.js behaviour of starting them at their top
+    let activate = |y: F, edge: EdgeEntry<F>| {
+        if shifted {
+            ActiveEdgeEntry::at(y, edge)
+        } else {
+            ActiveEdgeEntry { s: y, edge }
+        }
+    };
 
     loop {
         if !edges.is_empty() {
@@ -156,13 +248,13 @@ where
 
                 removed_elements
                     .into_iter()
-                    .for_each(|ee| active_edges.push(ActiveEdgeEntry { s: y, edge: ee }));
+                    .for_each(|ee| active_edges.push(activate(y, ee)));
             } else {
                 let removed_elements = edges.splice(0..edges.len(), vec![]);
 
                 removed_elements
                     .into_iter()
-                    .for_each(|ee| active_edges.push(ActiveEdgeEntry { s: y, edge: ee }));
+                    .for_each(|ee| active_edges.push(activate(y, ee)));
             }
         }
 
@@ -180,7 +272,22 @@ where
                 }
             }
         });
-        if active_edges.len() > 1 {
+        if union {
+            let mut winding = 0;
+            let mut span_start = F::zero();
+            for ae in active_edges.iter() {
+                let previous = winding;
+                winding += ae.edge.winding;
+                if previous == 0 && winding != 0 {
+                    span_start = ae.edge.x;
+                } else if previous != 0 && winding == 0 {
+                    lines.push(Line::from(&[
+                        euclid::Point2D::new(span_start, y),
+                        euclid::Point2D::new(ae.edge.x, y),
+                    ]));
+                }
+            }
+        } else if active_edges.len() > 1 {
             active_edges[..].chunks(2).for_each(|ae| {
                 let ce = &ae[0];
                 let ne = &ae[1];
@@ -212,8 +319,53 @@ where
     F: Float + Trig + FromPrimitive,
     P: BorrowMut<Vec<Vec<Point2D<F>>>>,
 {
-    fn fill_polygons(&self, mut polygon_list: P, o: &mut Options) -> crate::core::OpSet<F> {
-        let lines = polygon_hachure_lines(polygon_list.borrow_mut(), o);
+    fn fill_polygons(&self, polygon_list: P, o: &mut Options) -> crate::core::OpSet<F> {
+        self.fill_polygons_with_offset(polygon_list, o, F::zero())
+    }
+}
+
+impl<F: Float + Trig + FromPrimitive> ScanlineHachureFiller<F> {
+    pub fn new() -> Self {
+        ScanlineHachureFiller { _phantom: PhantomData }
+    }
+
+    /// Fills like [`PatternFiller::fill_polygons`] with the scan lines shifted across
+    /// themselves by `offset`.
+    pub(crate) fn fill_polygons_with_offset<P>(
+        &self,
+        mut polygon_list: P,
+        o: &mut Options,
+        offset: F,
+    ) -> OpSet<F>
+    where
+        P: BorrowMut<Vec<Vec<Point2D<F>>>>,
+    {
+        let mut lines = polygon_hachure_lines_with_offset(polygon_list.borrow_mut(), o, offset);
+        if let Some(jitter) = o.fill_end_jitter {
+            lines = jitter_line_ends(lines, _c(jitter), o);
+        }
+        match o.hachure_overdraw {
+            Some(overdraw) if overdraw != 0.0 => lines = extend_line_ends(lines, _c(overdraw)),
+            _ => (),
+        }
+        let lines = match o.fill_line_dash.clone() {
+            Some(dash) => {
+                let offset = o.fill_line_dash_offset.unwrap_or(0.0);
+                lines
+                    .iter()
+                    .flat_map(|l| dash_line(l, &dash, offset))
+                    .collect()
+            }
+            None => lines,
+        };
+        if o.hachure_as_fill.unwrap_or(false) {
+            return OpSet {
+                op_set_type: crate::core::OpSetType::FillPath,
+                ops: ScanlineHachureFiller::render_quads(lines, o),
+                size: None,
+                path: None,
+            };
+        }
         let ops = ScanlineHachureFiller::render_lines(lines, o);
         OpSet {
             op_set_type: crate::core::OpSetType::FillSketch,
@@ -222,11 +374,37 @@ where
             path: None,
         }
     }
-}
 
-impl<F: Float + Trig + FromPrimitive> ScanlineHachureFiller<F> {
-    pub fn new() -> Self {
-        ScanlineHachureFiller { _phantom: PhantomData }
+    /// Turns every line into a rectangle of `fill_weight` width, one move and three line
+    /// ops each. The normal is always the direction turned the same way, so all quads wind
+    /// alike and crossing quads stay filled under nonzero winding.
+    fn render_quads(lines: Vec<Line<F>>, o: &Options) -> Vec<crate::core::Op<F>> {
+        let mut weight = o.fill_weight.unwrap_or(-1.0);
+        if weight < 0.0 {
+            weight = o.stroke_width.unwrap_or(1.0) / 2.0;
+        }
+        let half_weight = _c::<F>(weight / 2.0);
+        let mut ops = vec![];
+        for line in lines.iter() {
+            let direction = line.end_point - line.start_point;
+            if direction.length() == F::zero() {
+                continue;
+            }
+            let normal = Vector2D::new(-direction.y, direction.x).normalize() * half_weight;
+            let corners = [
+                line.start_point + normal,
+                line.end_point + normal,
+                line.end_point - normal,
+                line.start_point - normal,
+            ];
+            for (i, corner) in corners.iter().enumerate() {
+                ops.push(Op {
+                    op: if i == 0 { OpType::Move } else { OpType::LineTo },
+                    data: vec![corner.x, corner.y],
+                });
+            }
+        }
+        ops
     }
 
     fn render_lines(lines: Vec<Line<F>>, o: &mut Options) -> Vec<crate::core::Op<F>> {
@@ -246,11 +424,100 @@ impl<F: Float + Trig + FromPrimitive> ScanlineHachureFiller<F> {
     }
 }
 
+/// Moves both ends of every line along the line by a random distance in `-jitter..jitter`.
+fn jitter_line_ends<F: Float + Trig + FromPrimitive>(
+    lines: Vec<Line<F>>,
+    jitter: F,
+    o: &mut Options,
+) -> Vec<Line<F>> {
+    lines
+        .into_iter()
+        .map(|line| {
+            let length = line.length();
+            if length == F::zero() {
+                return line;
+            }
+            let direction = (line.end_point - line.start_point) / length;
+            let mut offset = || jitter * (_cc::<F>(o.random()) * _c(2.0) - F::one());
+            let start = line.start_point - direction * offset();
+            let end = line.end_point + direction * offset();
+            Line::from(&[start, end])
+        })
+        .collect()
+}
+
+/// Moves both ends of every line outward by `distance` along the line.
+fn extend_line_ends<F: Float + Trig + FromPrimitive>(
+    lines: Vec<Line<F>>,
+    distance: F,
+) -> Vec<Line<F>> {
+    lines
+        .into_iter()
+        .map(|line| {
+            let length = line.length();
+            if length == F::zero() {
+                return line;
+            }
+            let direction = (line.end_point - line.start_point) / length;
+            Line::from(&[
+                line.start_point - direction * distance,
+                line.end_point + direction * distance,
+            ])
+        })
+        .collect()
+}
+
+/// Splits `line` into the "on" parts of the dash pattern `dash`, shifted by `offset`.
+/// Odd length patterns are repeated once, as in svg.
+fn dash_line<F: Float + Trig + FromPrimitive>(
+    line: &Line<F>,
+    dash: &[f64],
+    offset: f64,
+) -> Vec<Line<F>> {
+    let mut pattern = dash.to_vec();
+    if pattern.len() % 2 == 1 {
+        pattern.extend_from_slice(dash);
+    }
+    let total: f64 = pattern.iter().sum();
+    if pattern.is_empty() || total <= 0.0 || pattern.iter().any(|d| *d < 0.0) {
+        return vec![line.clone()];
+    }
+
+    let mut index = 0;
+    let mut phase = offset.rem_euclid(total);
+    while phase >= pattern[index] {
+        phase -= pattern[index];
+        index = (index + 1) % pattern.len();
+    }
+    let mut remaining = pattern[index] - phase;
+
+    let length = line.length().to_f64().unwrap();
+    let mut position = 0.0;
+    let mut dashes = vec![];
+    while position < length {
+        let end = f64::min(position + remaining, length);
+        if index % 2 == 0 && end > position {
+            let at = |d: f64| {
+                line.start_point
+                    .lerp(line.end_point, F::from(d / length).unwrap())
+            };
+            dashes.push(Line::from(&[at(position), at(end)]));
+        }
+        position = end;
+        index = (index + 1) % pattern.len();
+        remaining = pattern[index];
+    }
+    dashes
+}
+
 #[cfg(test)]
 mod test {
     use euclid::point2;
 
-    use crate::geometry::Line;
+    use super::ScanlineHachureFiller;
+    use crate::core::{OpSetType, OpType, OptionsBuilder};
+    use crate::filler::traits::PatternFiller;
+    use crate::geometry::{rotate_points, Line};
 
     #[test]
     fn straight_hachure_lines() {
@@ -299,7 +566,238 @@ mod test {
                 point2(1.0, 0.9000000134110451),
             ]),
         ];
-        let result = super::straight_hachure_lines(&mut input, 0.1);
+        let result = super::straight_hachure_lines(&mut input, 0.1, None, 0.0, false);
         assert_eq!(expected, result);
     }
+
+    #[test]
+    fn seeded_hachure_phase_is_stable() {
+        let square = |dx: f32, dy: f32| {
+            vec![vec![
+                point2(dx, dy),
+                point2(dx + 20.0, dy),
+                point2(dx + 20.0, dy + 20.0),
+                point2(dx, dy + 20.0),
+            ]]
+        };
+        for angle in [-90.0, -41.0] {
+            let options = OptionsBuilder::default()
+                .seed(7_u64)
+                .seeded_hachure_phase(true)
+                .hachure_angle(angle)
+                .hachure_gap(4.0)
+                .build()
+                .unwrap();
+            // distance of every line from the origin across the lines, modulo the gap
+            let grid_phase = |lines: &[Line<f32>]| -> Vec<f32> {
+                lines
+                    .iter()
+                    .map(|l| {
+                        rotate_points(&[l.start_point], &point2(0.0, 0.0), angle + 90.0)[0]
+                            .y
+                            .rem_euclid(4.0)
+                    })
+                    .collect()
+            };
+            let first = super::polygon_hachure_lines(&mut square(0.0, 0.0), &options);
+            let again = super::polygon_hachure_lines(&mut square(0.0, 0.0), &options);
+            assert_eq!(first, again);
+
+            let shifted = super::polygon_hachure_lines(&mut square(1.3, 2.7), &options);
+            let anchor = grid_phase(&first)[0];
+            for phase in grid_phase(&first).into_iter().chain(grid_phase(&shifted)) {
+                let d = (phase - anchor).rem_euclid(4.0);
+                assert!(d.min(4.0 - d) < 1e-3);
+            }
+        }
+
+        // off by default, so the seed does not move the lines
+        let lines = |seed: u64| {
+            let options = OptionsBuilder::default()
+                .seed(seed)
+                .hachure_gap(4.0)
+                .build()
+                .unwrap();
+            super::polygon_hachure_lines(&mut square(0.0, 0.0), &options)
+        };
+        assert_eq!(lines(7), lines(8));
+    }
+
+    #[test]
+    fn fill_line_dash_splits_hachure_lines() {
+        let line = Line::from(&[point2(0.0f32, 0.0), point2(20.0, 0.0)]);
+        let dashes = super::dash_line(&line, &[4.0, 2.0], 0.0);
+        assert_eq!(
+            dashes,
+            vec![
+                Line::from(&[point2(0.0, 0.0), point2(4.0, 0.0)]),
+                Line::from(&[point2(6.0, 0.0), point2(10.0, 0.0)]),
+                Line::from(&[point2(12.0, 0.0), point2(16.0, 0.0)]),
+                Line::from(&[point2(18.0, 0.0), point2(20.0, 0.0)]),
+            ]
+        );
+
+        let square = || {
+            vec![vec![
+                point2(0.0f32, 0.0),
+                point2(40.0, 0.0),
+                point2(40.0, 40.0),
+                point2(0.0, 40.0),
+            ]]
+        };
+        let moves = |o: &mut crate::core::Options| {
+            ScanlineHachureFiller::new()
+                .fill_polygons(square(), o)
+                .ops
+                .iter()
+                .filter(|op| op.op == OpType::Move)
+                .count()
+        };
+        let plain = moves(&mut OptionsBuilder::default().build().unwrap());
+        let dashed = moves(
+            &mut OptionsBuilder::default()
+                .fill_line_dash(vec![4.0, 4.0])
+                .build()
+                .unwrap(),
+        );
+        assert!(dashed >= plain * 4);
+    }
+
+    #[test]
+    fn hachure_as_fill_emits_quads() {
+        let square = vec![vec![
+            point2(0.0f32, 0.0),
+            point2(40.0, 0.0),
+            point2(40.0, 40.0),
+            point2(0.0, 40.0),
+        ]];
+        let mut o = OptionsBuilder::default()
+            .hachure_as_fill(true)
+            .fill_weight(2.0)
+            .build()
+            .unwrap();
+        let set = ScanlineHachureFiller::new().fill_polygons(square, &mut o);
+
+        assert_eq!(set.op_set_type, OpSetType::FillPath);
+        assert!(!set.ops.is_empty());
+        assert_eq!(set.ops.len() % 4, 0);
+        for quad in set.ops.chunks(4) {
+            assert_eq!(quad[0].op, OpType::Move);
+            assert!(quad[1..].iter().all(|op| op.op == OpType::LineTo));
+            let width = ((quad[1].data[0] - quad[2].data[0]).powi(2)
+                + (quad[1].data[1] - quad[2].data[1]).powi(2))
+            .sqrt();
+            assert!((width - 2.0).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn fill_end_jitter_moves_endpoints_along_line() {
+        let lines = vec![
+            Line::from(&[point2(0.0f32, 0.0), point2(40.0, 0.0)]),
+            Line::from(&[point2(0.0f32, 5.0), point2(40.0, 5.0)]),
+        ];
+        let mut o = OptionsBuilder::default().build().unwrap();
+        let jittered = super::jitter_line_ends(lines.clone(), 2.0, &mut o);
+
+        assert_ne!(jittered, lines);
+        for (j, l) in jittered.iter().zip(lines.iter()) {
+            assert_eq!(j.start_point.y, l.start_point.y);
+            assert_eq!(j.end_point.y, l.end_point.y);
+            assert!((j.start_point.x - l.start_point.x).abs() <= 2.0);
+            assert!((j.end_point.x - l.end_point.x).abs() <= 2.0);
+        }
+    }
+
+    #[test]
+    fn hachure_overdraw_crosses_polygon_edges() {
+        let options = OptionsBuilder::default()
+            .hachure_angle(-90.0)
+            .hachure_gap(4.0)
+            .build()
+            .unwrap();
+        let mut square = vec![vec![
+            point2(0.0f32, 0.0),
+            point2(20.0, 0.0),
+            point2(20.0, 20.0),
+            point2(0.0, 20.0),
+        ]];
+        let lines = super::polygon_hachure_lines(&mut square, &options);
+        let extended = super::extend_line_ends(lines.clone(), 3.0);
+
+        assert!(!lines.is_empty());
+        for (e, l) in extended.iter().zip(lines.iter()) {
+            assert!((e.length() - l.length() - 6.0).abs() < 1e-3);
+            assert!(e.start_point.x < 0.0 || e.start_point.x > 20.0);
+            assert!(e.end_point.x < 0.0 || e.end_point.x > 20.0);
+        }
+
+        let x_extent = |overdraw: f32| {
+            let mut o = OptionsBuilder::default()
+                .roughness(0.0)
+                .hachure_angle(-90.0)
+                .hachure_gap(4.0)
+                .hachure_overdraw(overdraw)
+                .disable_multi_stroke_fill(true)
+                .build()
+                .unwrap();
+            let ops = ScanlineHachureFiller::new()
+                .fill_polygons(square.clone(), &mut o)
+                .ops;
+            ops.iter()
+                .map(|op| op.data[op.data.len() - 2])
+                .fold((f32::MAX, f32::MIN), |(min, max), x| {
+                    (min.min(x), max.max(x))
+                })
+        };
+        let (min, max) = x_extent(0.0);
+        assert!(min.abs() < 1e-3 && (max - 20.0).abs() < 1e-3);
+        let (min, max) = x_extent(3.0);
+        assert!((min + 3.0).abs() < 1e-3 && (max - 23.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn merge_hachure_segments_joins_adjacent_polygons() {
+        let square = |x: f32| {
+            vec![
+                point2(x, 0.0),
+                point2(x + 20.0, 0.0),
+                point2(x + 20.0, 20.0),
+                point2(x, 20.0),
+            ]
+        };
+        let mut options = OptionsBuilder::default()
+            .hachure_angle(-90.0)
+            .hachure_gap(4.0)
+            .build()
+            .unwrap();
+        let split = super::polygon_hachure_lines(&mut vec![square(0.0), square(20.0)], &options);
+        options.merge_hachure_segments = Some(true);
+        let merged = super::polygon_hachure_lines(&mut vec![square(0.0), square(20.0)], &options);
+
+        assert_eq!(split.len(), merged.len() * 2);
+        assert!(merged.iter().all(|l| (l.length() - 40.0).abs() < 1e-3));
+    }
+
+    #[test]
+    fn hachure_gap_is_clamped_to_fill_weight() {
+        let options = OptionsBuilder::default()
+            .hachure_angle(-90.0)
+            .hachure_gap(1.0)
+            .fill_weight(4.0)
+            .build()
+            .unwrap();
+        let mut square = vec![vec![
+            point2(0.0f32, 0.0),
+            point2(30.0, 0.0),
+            point2(30.0, 30.0),
+            point2(0.0, 30.0),
+        ]];
+        let lines = super::polygon_hachure_lines(&mut square, &options);
+
+        assert!(lines.len() <= 6);
+        for pair in lines.windows(2) {
+            assert!((pair[1].start_point.y - pair[0].start_point.y - 6.0).abs() < 1e-3);
+        }
+    }
 }
@@ -9,6 +9,7 @@ use super::scan_line_hachure::polygon_hachure_lines;
 use super::traits::PatternFiller;
 use crate::core::{OpSet, OpSetType, Options, _c};
 use crate::geometry::Line;
+use crate::renderer::_rounded_polyline;
 
 pub struct ZigZagFiller<F> {
     _phantom: PhantomData<F>,
@@ -47,7 +48,22 @@ where
             }
         }
 
-        let ops = ZigZagFiller::render_lines(zig_zag_lines, o);
+        let ops = if o.zigzag_round.unwrap_or(false) {
+            // both lines of a pair end at the same point; join them into one rounded turn there
+            let mut ops = vec![];
+            for pair in zig_zag_lines.chunks(2) {
+                if let [a, b] = pair {
+                    ops.extend(_rounded_polyline(
+                        &[a.start_point, a.end_point, b.start_point],
+                        o,
+                    ));
+                }
+            }
+            ops
+        } else {
+            ZigZagFiller::render_lines(zig_zag_lines, o)
+        };
+
         return OpSet {
             ops,
             op_set_type: OpSetType::FillSketch,
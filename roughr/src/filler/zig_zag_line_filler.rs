@@ -9,7 +9,7 @@ use super::scan_line_hachure::polygon_hachure_lines;
 use super::traits::PatternFiller;
 use crate::core::{Op, OpSet, OpSetType, Options, _c};
 use crate::geometry::Line;
-use crate::renderer::_double_line;
+use crate::renderer::{_double_line, _rounded_polyline};
 
 pub struct ZigZagLineFiller<F> {
     _phantom: PhantomData<F>,
@@ -51,8 +51,10 @@ impl<F: Float + Trig + FromPrimitive> ZigZagLineFiller<F> {
     }
 
     fn zig_zag_lines(lines: &[Line<F>], zig_zag_offset: F, o: &mut Options) -> Vec<Op<F>> {
+        let round = o.zigzag_round.unwrap_or(false);
         let mut ops = vec![];
         for line in lines.iter() {
+            let mut turns = vec![];
             let length = line.length();
             let count = length / (_c::<F>(2.0) * zig_zag_offset);
             let mut p1 = line.start_point;
@@ -80,10 +82,19 @@ impl<F: Float + Trig + FromPrimitive> ZigZagLineFiller<F> {
                     start.x + dz * num_traits::Float::cos(alpha + _c::<F>(f32::PI() / 4.0)),
                     start.y + dz * num_traits::Float::sin(alpha + _c::<F>(f32::PI() / 4.0)),
                 );
+                if round {
+                    if turns.is_empty() {
+                        turns.push(start);
+                    }
+                    turns.push(middle);
+                    turns.push(end);
+                    continue;
+                }
                 ops.extend(_double_line(start.x, start.y, middle.x, middle.y, o, false));
 
                 ops.extend(_double_line(middle.x, middle.y, end.x, end.y, o, false));
             }
+            ops.extend(_rounded_polyline(&turns, o));
         }
         ops
     }
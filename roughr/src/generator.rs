@@ -1,15 +1,18 @@
 use std::fmt::{Display, Write};
 use std::ops::MulAssign;
 
-use euclid::default::Point2D;
+use euclid::default::{Point2D, Vector2D};
 use euclid::Trig;
 use num_traits::{Float, FromPrimitive};
-use points_on_curve::{curve_to_bezier, points_on_bezier_curves};
+use points_on_curve::{catmull_rom_to_bezier, curve_to_bezier, points_on_bezier_curves, simplify};
 use svgtypes::PathSegment;
+#[cfg(feature = "svg-import")]
+use svgtypes::PointsParser;
 
 use crate::core::{
     Drawable,
     FillStyle,
+    Op,
     OpSet,
     OpSetType,
     OpType,
@@ -18,23 +21,32 @@ use crate::core::{
     PathInfo,
     _c,
 };
-use crate::geometry::{convert_bezier_quadratic_to_cubic, BezierQuadratic};
+use crate::error::{check_finite, check_size, RoughrError};
+use crate::geometry::{convert_bezier_quadratic_to_cubic, dash_polyline, BezierQuadratic};
 use crate::points_on_path::{points_on_path, points_on_segments};
 use crate::renderer::{
     bezier_cubic,
     bezier_quadratic,
     curve,
+    dot,
+    ellipse_increment,
     ellipse_with_params,
-    generate_ellipse_params,
+    highlight_ellipse,
     line,
     linear_path,
+    linear_path_weighted,
+    path_fills,
     pattern_fill_arc,
     pattern_fill_polygons,
+    randomize_ellipse_params,
     rectangle,
     solid_fill_polygon,
     svg_path,
     svg_segments,
+    EllipseParams,
 };
+#[cfg(feature = "svg-import")]
+use crate::svg_elements::{svg_elements, svg_number, svg_paint};
 
 pub struct Generator {
     default_options: Options,
@@ -70,10 +82,30 @@ impl Generator {
         }
     }
 
+    /// Drawable for a shape of zero size at (`x`, `y`): a single dot when
+    /// `zero_size_dot` is set, otherwise nothing.
+    fn zero_size<F>(&self, name: &str, x: F, y: F, mut options: Options) -> Drawable<F>
+    where
+        F: Float + Trig + FromPrimitive,
+    {
+        let sets = if options.zero_size_dot.unwrap_or(false) {
+            vec![dot(x, y, &mut options)]
+        } else {
+            vec![]
+        };
+        self.d(name, &sets, &Some(options))
+    }
+
     pub fn line<F>(&self, x1: F, y1: F, x2: F, y2: F, options: &Option<Options>) -> Drawable<F>
     where
         F: Float + Trig + FromPrimitive,
     {
+        if x1 == x2 && y1 == y2 {
+            let options = options
+                .clone()
+                .unwrap_or_else(|| self.default_options.clone());
+            return self.zero_size("line", x1, y1, options);
+        }
         self.d(
             "line",
             &[line(
@@ -89,6 +121,37 @@ impl Generator {
         )
     }
 
+    /// Same as [`Generator::line`] but also returns the start and end points the line was
+    /// actually drawn between: the start of its first stroke and the end of its last one.
+    /// Useful for attaching other shapes when `preserve_vertices` is off.
+    pub fn line_detailed<F>(
+        &self,
+        x1: F,
+        y1: F,
+        x2: F,
+        y2: F,
+        options: &Option<Options>,
+    ) -> (Drawable<F>, Point2D<F>, Point2D<F>)
+    where
+        F: Float + Trig + FromPrimitive,
+    {
+        let drawable = self.line(x1, y1, x2, y2, options);
+        let ops = drawable
+            .sets
+            .first()
+            .map(|set| set.ops.as_slice())
+            .unwrap_or(&[]);
+        let start = ops
+            .first()
+            .map(|op| Point2D::new(op.data[0], op.data[1]))
+            .unwrap_or_else(|| Point2D::new(x1, y1));
+        let end = ops
+            .last()
+            .map(|op| Point2D::new(op.data[op.data.len() - 2], op.data[op.data.len() - 1]))
+            .unwrap_or_else(|| Point2D::new(x2, y2));
+        (drawable, start, end)
+    }
+
     pub fn rectangle<F>(
         &self,
         x: F,
@@ -97,6 +160,22 @@ impl Generator {
         height: F,
         options: &Option<Options>,
     ) -> Drawable<F>
+    where
+        F: Float + Trig + FromPrimitive,
+    {
+        self.rectangle_detailed(x, y, width, height, options).0
+    }
+
+    /// Same as [`Generator::rectangle`] but also returns the polygons used for
+    /// filling, so callers can keep them for hit testing without recomputing.
+    pub fn rectangle_detailed<F>(
+        &self,
+        x: F,
+        y: F,
+        width: F,
+        height: F,
+        options: &Option<Options>,
+    ) -> (Drawable<F>, Vec<Vec<Point2D<F>>>)
     where
         F: Float + Trig + FromPrimitive,
     {
@@ -104,28 +183,75 @@ impl Generator {
         let mut options = options
             .clone()
             .unwrap_or_else(|| self.default_options.clone());
+        if width == F::zero() && height == F::zero() {
+            return (self.zero_size("rectangle", x, y, options), vec![]);
+        }
         let outline = rectangle(x, y, width, height, &mut options);
-        if options.fill.is_some() {
-            let points = vec![
-                Point2D::new(x, y),
-                Point2D::new(x + width, y),
-                Point2D::new(x + width, y + height),
-                Point2D::new(x, y + height),
-            ];
+        let corners = vec![
+            Point2D::new(x, y),
+            Point2D::new(x + width, y),
+            Point2D::new(x + width, y + height),
+            Point2D::new(x, y + height),
+        ];
+        // fill the corners the outline was actually drawn through so fill and stroke line up
+        let points = if options.clean_fill.unwrap_or(false) {
+            corners
+        } else {
+            first_pass_starts(&outline.ops, 4).unwrap_or(corners)
+        };
+        if options.fill.is_some() && width != F::zero() && height != F::zero() {
             if options.fill_style == Some(FillStyle::Solid) {
-                paths.push(solid_fill_polygon(&vec![points], &mut options));
+                paths.push(solid_fill_polygon(&vec![points.clone()], &mut options));
             } else {
-                paths.push(pattern_fill_polygons(vec![points], &mut options));
+                paths.push(pattern_fill_polygons(vec![points.clone()], &mut options));
             }
         }
         if options.stroke.is_some() {
             paths.push(outline);
         }
 
-        self.d("rectangle", &paths, &Some(options))
+        (self.d("rectangle", &paths, &Some(options)), vec![points])
     }
 
-    pub fn ellipse<F>(
+    /// Draws a rough border `inset` away from every edge of a `width` by `height` canvas.
+    pub fn frame<F>(&self, width: F, height: F, inset: F, options: &Option<Options>) -> Drawable<F>
+    where
+        F: Float + Trig + FromPrimitive,
+    {
+        let mut frame = self.rectangle(
+            inset,
+            inset,
+            width - inset - inset,
+            height - inset - inset,
+            options,
+        );
+        frame.shape = "frame".into();
+        frame
+    }
+
+    /// Same as [`Generator::frame`] with a second border drawn `gap` further inside.
+    pub fn double_frame<F>(
+        &self,
+        width: F,
+        height: F,
+        inset: F,
+        gap: F,
+        options: &Option<Options>,
+    ) -> Drawable<F>
+    where
+        F: Float + Trig + FromPrimitive,
+    {
+        let mut frame = self.frame(width, height, inset, options);
+        let mut inner_options = frame.options.clone();
+        inner_options.seed = inner_options.seed.map(|seed| seed + 1);
+        let inner = self.frame(width, height, inset + gap, &Some(inner_options));
+        frame.sets.extend(inner.sets);
+        frame
+    }
+
+    /// The outline [`Generator::rectangle`] would draw with all randomness turned off, as a
+    /// single stroke without fill. Useful as a reference to measure a sketch against.
+    pub fn clean_rectangle<F>(
         &self,
         x: F,
         y: F,
@@ -136,30 +262,123 @@ impl Generator {
     where
         F: Float + Trig + FromPrimitive,
     {
-        let mut paths = vec![];
+        self.rectangle(x, y, width, height, &Some(self.clean_options(options)))
+    }
+
+    /// The outline [`Generator::ellipse`] would draw with all randomness turned off, as a
+    /// single stroke without fill.
+    pub fn clean_ellipse<F>(
+        &self,
+        x: F,
+        y: F,
+        width: F,
+        height: F,
+        options: &Option<Options>,
+    ) -> Drawable<F>
+    where
+        F: Float + Trig + FromPrimitive,
+    {
+        self.ellipse(x, y, width, height, &Some(self.clean_options(options)))
+    }
+
+    fn clean_options(&self, options: &Option<Options>) -> Options {
         let mut options = options
             .clone()
             .unwrap_or_else(|| self.default_options.clone());
-        let ellipse_params = generate_ellipse_params(width, height, &mut options);
+        options.deterministic_preview = Some(true);
+        options.disable_multi_stroke = Some(true);
+        options.fill = None;
+        options
+    }
+
+    pub fn ellipse<F>(
+        &self,
+        x: F,
+        y: F,
+        width: F,
+        height: F,
+        options: &Option<Options>,
+    ) -> Drawable<F>
+    where
+        F: Float + Trig + FromPrimitive,
+    {
+        self.ellipse_detailed(x, y, width, height, options).0
+    }
+
+    /// Same as [`Generator::ellipse`] but also returns the estimated ellipse
+    /// polygon used for filling.
+    pub fn ellipse_detailed<F>(
+        &self,
+        x: F,
+        y: F,
+        width: F,
+        height: F,
+        options: &Option<Options>,
+    ) -> (Drawable<F>, Vec<Vec<Point2D<F>>>)
+    where
+        F: Float + Trig + FromPrimitive,
+    {
+        let options = options
+            .clone()
+            .unwrap_or_else(|| self.default_options.clone());
+        let increment = ellipse_increment(width, height, &options);
+        self.ellipse_with_increment(x, y, width, height, increment, options)
+    }
+
+    fn ellipse_with_increment<F>(
+        &self,
+        x: F,
+        y: F,
+        width: F,
+        height: F,
+        increment: F,
+        mut options: Options,
+    ) -> (Drawable<F>, Vec<Vec<Point2D<F>>>)
+    where
+        F: Float + Trig + FromPrimitive,
+    {
+        if width == F::zero() && height == F::zero() {
+            return (self.zero_size("ellipse", x, y, options), vec![]);
+        }
+        let mut paths = vec![];
+        let ellipse_params = randomize_ellipse_params(increment, width, height, &mut options);
         let ellipse_response = ellipse_with_params(x, y, &mut options, &ellipse_params);
-        if options.fill.is_some() {
-            if options.fill_style == Some(FillStyle::Solid) {
+        let mut points = ellipse_response.estimated_points;
+        if options.fill.is_some() && width != F::zero() && height != F::zero() {
+            if options.clean_fill.unwrap_or(false) {
+                // trace the exact ellipse without roughness, the outline above stays rough
+                let roughness = options.roughness.replace(0.0);
+                let exact = EllipseParams {
+                    increment,
+                    rx: Float::abs(width / _c(2.0)),
+                    ry: Float::abs(height / _c(2.0)),
+                };
+                let shape = ellipse_with_params(x, y, &mut options, &exact);
+                points = shape.estimated_points;
+                if options.fill_style == Some(FillStyle::Solid) {
+                    let mut fill = shape.opset;
+                    fill.op_set_type = OpSetType::FillPath;
+                    paths.push(fill);
+                } else {
+                    paths.push(pattern_fill_polygons(vec![points.clone()], &mut options));
+                }
+                options.roughness = roughness;
+            } else if options.fill_style == Some(FillStyle::Solid) {
                 let mut shape = ellipse_with_params(x, y, &mut options, &ellipse_params).opset;
                 shape.op_set_type = OpSetType::FillPath;
                 paths.push(shape);
             } else {
-                paths.push(pattern_fill_polygons(
-                    vec![ellipse_response.estimated_points],
-                    &mut options,
-                ));
+                paths.push(pattern_fill_polygons(vec![points.clone()], &mut options));
             }
         }
         if options.stroke.is_some() {
             paths.push(ellipse_response.opset);
         }
-        self.d("ellipse", &paths, &Some(options))
+        (self.d("ellipse", &paths, &Some(options)), vec![points])
     }
 
+    /// Draws a circle centered at `(x, y)`. Note that the size is given as a
+    /// diameter; use [`Generator::circle_radius`] to pass a radius instead.
     pub fn circle<F>(&self, x: F, y: F, diameter: F, options: &Option<Options>) -> Drawable<F>
     where
         F: Float + Trig + FromPrimitive,
@@ -169,6 +388,110 @@ impl Generator {
         shape
     }
 
+    /// Draws an open, single pass ellipse around (`cx`, `cy`) whose end overshoots its
+    /// start, like a quick pen circle used to highlight something.
+    pub fn highlight_ellipse<F>(
+        &self,
+        cx: F,
+        cy: F,
+        rx: F,
+        ry: F,
+        options: &Option<Options>,
+    ) -> Drawable<F>
+    where
+        F: Float + Trig + FromPrimitive,
+    {
+        let mut options = options
+            .clone()
+            .unwrap_or_else(|| self.default_options.clone());
+        let opset = highlight_ellipse(cx, cy, rx, ry, &mut options);
+        self.d("highlight_ellipse", &[opset], &Some(options))
+    }
+
+    /// Draws circles of the same `radius` at every center. The sampling step is computed
+    /// once for all of them. Circle `i` is drawn with the seed stepped by `i`, so it matches
+    /// [`Generator::circle_radius`] called with that seed.
+    pub fn circle_batch<F>(
+        &self,
+        centers: &[Point2D<F>],
+        radius: F,
+        options: &Option<Options>,
+    ) -> Vec<Drawable<F>>
+    where
+        F: Float + Trig + FromPrimitive,
+    {
+        let options = options
+            .clone()
+            .unwrap_or_else(|| self.default_options.clone());
+        let diameter = radius + radius;
+        let increment = ellipse_increment(diameter, diameter, &options);
+        centers
+            .iter()
+            .enumerate()
+            .map(|(i, center)| {
+                let mut circle_options = options.clone();
+                circle_options.seed = options.seed.map(|s| s.wrapping_add(i as u64));
+                circle_options.randomizer = None;
+                let (mut shape, _) = self.ellipse_with_increment(
+                    center.x,
+                    center.y,
+                    diameter,
+                    diameter,
+                    increment,
+                    circle_options,
+                );
+                shape.shape = "circle".into();
+                shape
+            })
+            .collect()
+    }
+
+    /// Draws a circle centered at `(x, y)` with the given `radius`.
+    pub fn circle_radius<F>(&self, x: F, y: F, radius: F, options: &Option<Options>) -> Drawable<F>
+    where
+        F: Float + Trig + FromPrimitive,
+    {
+        self.circle(x, y, radius + radius, options)
+    }
+
+    /// Returns an svg path string for a circle. When roughness is near zero the circle is
+    /// written as two `A` commands instead of flattened bezier curves, otherwise the
+    /// rough outline of [`Generator::circle`] is exported.
+    pub fn circle_svg_path<F>(&self, x: F, y: F, diameter: F, options: &Option<Options>) -> String
+    where
+        F: Float + Trig + FromPrimitive + Display,
+    {
+        let roughness = options
+            .as_ref()
+            .unwrap_or(&self.default_options)
+            .roughness
+            .unwrap_or(1.0);
+        if roughness.abs() < 1e-3 {
+            let r = diameter.abs() / _c(2.0);
+            format!(
+                "M{} {} A{} {} 0 1 0 {} {} A{} {} 0 1 0 {} {} Z",
+                x + r,
+                y,
+                r,
+                r,
+                x - r,
+                y,
+                r,
+                r,
+                x + r,
+                y
+            )
+        } else {
+            self.circle(x, y, diameter, options)
+                .sets
+                .into_iter()
+                .filter(|set| set.op_set_type == OpSetType::Path)
+                .map(|set| Self::ops_to_path(set, None))
+                .collect::<Vec<_>>()
+                .join("")
+        }
+    }
+
     pub fn linear_path<F>(
         &self,
         points: &[Point2D<F>],
@@ -188,6 +511,65 @@ impl Generator {
         )
     }
 
+    /// Same as [`Generator::linear_path`] with a roughness weight per point. Weight 0 keeps
+    /// a vertex exactly at its coordinate, 1 roughens it like an unweighted path.
+    pub fn linear_path_weighted<F>(
+        &self,
+        points: &[(Point2D<F>, f32)],
+        close: bool,
+        options: &Option<Options>,
+    ) -> Drawable<F>
+    where
+        F: Float + Trig + FromPrimitive,
+    {
+        let mut options = options
+            .clone()
+            .unwrap_or_else(|| self.default_options.clone());
+        self.d(
+            "linear_path",
+            &[linear_path_weighted(points, close, &mut options)],
+            &Some(options),
+        )
+    }
+
+    /// Draws an open polyline through `points`, without the closing segment that
+    /// [`Generator::polygon`] adds.
+    pub fn open_path<F>(&self, points: &[Point2D<F>], options: &Option<Options>) -> Drawable<F>
+    where
+        F: Float + Trig + FromPrimitive,
+    {
+        self.linear_path(points, false, options)
+    }
+
+    /// Draws an open polyline through `points` as rough dashes of length `dash` separated
+    /// by `gap`. The dashes are part of the geometry, so no renderer dash setting is needed.
+    pub fn dashed_path<F>(
+        &self,
+        points: &[Point2D<F>],
+        dash: F,
+        gap: F,
+        options: &Option<Options>,
+    ) -> Drawable<F>
+    where
+        F: Float + Trig + FromPrimitive,
+    {
+        let mut options = options
+            .clone()
+            .unwrap_or_else(|| self.default_options.clone());
+        let mut dashes = OpSet {
+            op_set_type: OpSetType::Path,
+            ops: vec![],
+            size: None,
+            path: None,
+        };
+        for piece in dash_polyline(points, dash, gap) {
+            dashes
+                .ops
+                .extend(linear_path(&piece, false, &mut options).ops);
+        }
+        self.d("dashed_path", &[dashes], &Some(options))
+    }
+
     pub fn arc<F>(
         &self,
         x: F,
@@ -355,98 +737,368 @@ impl Generator {
         self.d("curve", &paths, &Some(options))
     }
 
-    pub fn polygon<F>(&self, points: &[Point2D<F>], options: &Option<Options>) -> Drawable<F>
+    /// Draws a single open rough stroke through raw input samples such as pointer events.
+    /// The samples are simplified to within the stroke width, smoothed into Bezier segments
+    /// and every segment is roughened.
+    pub fn freehand<F>(&self, points: &[Point2D<F>], options: &Option<Options>) -> Drawable<F>
     where
         F: Float + Trig + FromPrimitive + MulAssign + Display,
     {
         let mut options = options
             .clone()
             .unwrap_or_else(|| self.default_options.clone());
-        let mut paths = vec![];
-        let outline = linear_path(points, true, &mut options);
-        if options.fill.is_some() {
-            if options.fill_style == Some(FillStyle::Solid) {
-                paths.push(solid_fill_polygon(&vec![points.to_vec()], &mut options));
-            } else {
-                paths.push(pattern_fill_polygons(
-                    &mut vec![points.to_vec()],
-                    &mut options,
-                ));
-            }
-        }
-        if options.stroke.is_some() {
-            paths.push(outline);
+        if points.len() < 2 {
+            return self.d("freehand", &[], &Some(options));
         }
-        self.d("polygon", &paths, &Some(options))
+        let tolerance = _c::<F>(options.stroke_width.unwrap_or(1.0).max(1.0));
+        let simplified = simplify(points, tolerance);
+        let outline = match curve_to_bezier(&simplified, _c(0.0)) {
+            Some(bezier) => {
+                let mut ops = vec![];
+                for i in (1..bezier.len() - 2).step_by(3) {
+                    let start = bezier[i - 1];
+                    let set =
+                        bezier_cubic(start, bezier[i], bezier[i + 1], bezier[i + 2], &mut options);
+                    ops.extend(set.ops);
+                }
+                OpSet {
+                    op_set_type: OpSetType::Path,
+                    ops,
+                    path: None,
+                    size: None,
+                }
+            }
+            None => linear_path(&simplified, false, &mut options),
+        };
+        self.d("freehand", &[outline], &Some(options))
     }
 
-    pub fn path<F>(&self, d: String, options: &Option<Options>) -> Drawable<F>
+    /// Draws a rough sine wave from `from` to `to`, e.g. to underline text. `amplitude` is
+    /// the peak distance from the straight line and `wavelength` the length of one period.
+    /// A wavelength that is not a positive finite number draws a plain rough line, and very
+    /// short wavelengths are sampled with at most 4096 points.
+    pub fn wavy_line<F>(
+        &self,
+        from: Point2D<F>,
+        to: Point2D<F>,
+        amplitude: F,
+        wavelength: F,
+        options: &Option<Options>,
+    ) -> Drawable<F>
     where
         F: Float + Trig + FromPrimitive + MulAssign + Display,
     {
-        let mut options = options.clone().unwrap_or(self.default_options.clone());
-        let mut paths = vec![];
-        if d.is_empty() {
-            self.d("path", &paths, &Some(options))
+        if !wavelength.is_finite() || wavelength <= F::zero() {
+            return self.line(from.x, from.y, to.x, to.y, options);
+        }
+        let mut options = options
+            .clone()
+            .unwrap_or_else(|| self.default_options.clone());
+        let direction = to - from;
+        let length = direction.length();
+        let steps = (length / wavelength * _c(8.0))
+            .ceil()
+            .max(F::one())
+            .min(_c(4096.0));
+        let normal = if length > F::zero() {
+            Vector2D::new(-direction.y, direction.x) / length
         } else {
-            let simplified = options.simplification.map(|a| a < 1.0).unwrap_or(false);
-            let distance = if simplified {
-                _c::<F>(4.0) - _c::<F>(4.0) * _c::<F>(options.simplification.unwrap())
-            } else {
-                (_c::<F>(1.0) + _c::<F>(options.roughness.unwrap_or(1.0))) / _c::<F>(2.0)
-            };
-
-            let sets = points_on_path(d.clone(), Some(_c(1.0)), Some(distance));
-            if options.fill.is_some() {
-                if options.fill_style == Some(FillStyle::Solid) {
-                    paths.push(solid_fill_polygon(&sets, &mut options));
-                } else {
-                    paths.push(pattern_fill_polygons(sets.clone(), &mut options));
-                }
-            }
+            Vector2D::zero()
+        };
+        let points: Vec<Point2D<F>> = (0..=steps.to_usize().unwrap_or(1))
+            .map(|i| {
+                let t = F::from_usize(i).unwrap() / steps;
+                let phase = _c::<F>(2.0 * std::f32::consts::PI) * length * t / wavelength;
+                from.lerp(to, t) + normal * amplitude * Float::sin(phase)
+            })
+            .collect();
+        self.d("wavy_line", &[curve(&points, &mut options)], &Some(options))
+    }
 
-            if options.stroke.is_some() {
-                if simplified {
-                    sets.iter()
-                        .for_each(|s| paths.push(linear_path(s, false, &mut options)));
-                } else {
-                    paths.push(svg_path(d, &mut options));
-                }
-            }
+    /// Draws a `cols` by `rows` grid over the given rectangle. Every inner grid line is
+    /// shifted by a seeded random amount of up to `jitter`, so cells come out slightly
+    /// irregular. The outer border stays on the rectangle.
+    #[allow(clippy::too_many_arguments)]
+    pub fn jittered_grid<F>(
+        &self,
+        x: F,
+        y: F,
+        width: F,
+        height: F,
+        cols: usize,
+        rows: usize,
+        jitter: F,
+        options: &Option<Options>,
+    ) -> Drawable<F>
+    where
+        F: Float + Trig + FromPrimitive,
+    {
+        let mut options = options
+            .clone()
+            .unwrap_or_else(|| self.default_options.clone());
+        let boundaries = |start: F, size: F, count: usize, o: &mut Options| -> Vec<F> {
+            let count = count.max(1);
+            (0..=count)
+                .map(|i| {
+                    let exact =
+                        start + size * F::from_usize(i).unwrap() / F::from_usize(count).unwrap();
+                    if i == 0 || i == count {
+                        exact
+                    } else {
+                        exact + jitter * (_c::<F>(o.random() as f32) * _c(2.0) - F::one())
+                    }
+                })
+                .collect()
+        };
+        let columns = boundaries(x, width, cols, &mut options);
+        let rows = boundaries(y, height, rows, &mut options);
 
-            self.d("path", &paths, &Some(options))
+        let mut grid = OpSet {
+            op_set_type: OpSetType::Path,
+            ops: vec![],
+            size: None,
+            path: None,
+        };
+        for column in columns {
+            grid.ops
+                .extend(line(column, y, column, y + height, &mut options).ops);
+        }
+        for row in rows {
+            grid.ops
+                .extend(line(x, row, x + width, row, &mut options).ops);
         }
+        self.d("jittered_grid", &[grid], &Some(options))
     }
 
-    pub fn path_from_segments<F>(
+    /// Draws a dimension line from `from` to `to`: the shaft, an open arrowhead at both ends
+    /// and a perpendicular tick of `tick_length` across each end. The shaft, the two heads and
+    /// the two ticks are separate op sets, in that order.
+    pub fn dimension_line<F>(
         &self,
-        segments: Vec<PathSegment>,
+        from: Point2D<F>,
+        to: Point2D<F>,
+        tick_length: F,
         options: &Option<Options>,
     ) -> Drawable<F>
     where
-        F: Float + Trig + FromPrimitive + MulAssign + Display,
+        F: Float + Trig + FromPrimitive,
     {
-        let mut options = options.clone().unwrap_or(self.default_options.clone());
-        let mut paths = vec![];
-        if segments.is_empty() {
-            self.d("path", &paths, &Some(options))
+        let mut options = options
+            .clone()
+            .unwrap_or_else(|| self.default_options.clone());
+        let shaft = to - from;
+        let length = shaft.length();
+        let direction = if length > F::zero() {
+            shaft / length
         } else {
-            let simplified = options.simplification.map(|a| a < 1.0).unwrap_or(false);
-            let distance = if simplified {
-                _c::<F>(4.0) - _c::<F>(4.0) * _c::<F>(options.simplification.unwrap())
+            Vector2D::new(F::one(), F::zero())
+        };
+        let normal: Vector2D<F> = Vector2D::new(-direction.y, direction.x);
+        let head = tick_length.min(length / _c(2.0));
+        let spread = Float::tan(_c::<F>(25.0).to_radians());
+
+        let mut sets = vec![line(from.x, from.y, to.x, to.y, &mut options)];
+        for (tip, back) in [(from, direction), (to, -direction)] {
+            let base = tip + back * head;
+            let wing = normal * head * spread;
+            sets.push(linear_path(
+                &[base + wing, tip, base - wing],
+                false,
+                &mut options,
+            ));
+        }
+        let half_tick = normal * (tick_length / _c(2.0));
+        for end in [from, to] {
+            let (a, b) = (end + half_tick, end - half_tick);
+            sets.push(line(a.x, a.y, b.x, b.y, &mut options));
+        }
+        self.d("dimension_line", &sets, &Some(options))
+    }
+
+    /// Draws a check mark in the `size` by `size` box at `x`, `y` as a short and a long
+    /// stroke meeting near the bottom.
+    pub fn check_mark<F>(&self, x: F, y: F, size: F, options: &Option<Options>) -> Drawable<F>
+    where
+        F: Float + Trig + FromPrimitive,
+    {
+        let mut options = options
+            .clone()
+            .unwrap_or_else(|| self.default_options.clone());
+        let at = |u: f32, v: f32| (x + size * _c(u), y + size * _c(v));
+        let (start, corner, end) = (at(0.15, 0.55), at(0.4, 0.8), at(0.9, 0.15));
+        let sets = vec![
+            line(start.0, start.1, corner.0, corner.1, &mut options),
+            line(corner.0, corner.1, end.0, end.1, &mut options),
+        ];
+        self.d("check_mark", &sets, &Some(options))
+    }
+
+    /// Draws an X in the `size` by `size` box at `x`, `y` as two diagonal strokes crossing
+    /// at its center.
+    pub fn cross_mark<F>(&self, x: F, y: F, size: F, options: &Option<Options>) -> Drawable<F>
+    where
+        F: Float + Trig + FromPrimitive,
+    {
+        let mut options = options
+            .clone()
+            .unwrap_or_else(|| self.default_options.clone());
+        let inset = size * _c(0.15);
+        let (near, far) = (inset, size - inset);
+        let sets = vec![
+            line(x + near, y + near, x + far, y + far, &mut options),
+            line(x + far, y + near, x + near, y + far, &mut options),
+        ];
+        self.d("cross_mark", &sets, &Some(options))
+    }
+
+    pub fn polygon<F>(&self, points: &[Point2D<F>], options: &Option<Options>) -> Drawable<F>
+    where
+        F: Float + Trig + FromPrimitive + MulAssign + Display,
+    {
+        self.polygon_detailed(points, options).0
+    }
+
+    /// Same as [`Generator::polygon`] but also returns the polygons used for filling.
+    pub fn polygon_detailed<F>(
+        &self,
+        points: &[Point2D<F>],
+        options: &Option<Options>,
+    ) -> (Drawable<F>, Vec<Vec<Point2D<F>>>)
+    where
+        F: Float + Trig + FromPrimitive + MulAssign + Display,
+    {
+        let mut options = options
+            .clone()
+            .unwrap_or_else(|| self.default_options.clone());
+        let mut paths = vec![];
+        let outline = linear_path(points, true, &mut options);
+        if options.fill.is_some() {
+            if options.fill_style == Some(FillStyle::Solid) {
+                paths.push(solid_fill_polygon(&vec![points.to_vec()], &mut options));
+            } else {
+                paths.push(pattern_fill_polygons(
+                    &mut vec![points.to_vec()],
+                    &mut options,
+                ));
+            }
+        }
+        if options.stroke.is_some() {
+            paths.push(outline);
+        }
+        (
+            self.d("polygon", &paths, &Some(options)),
+            vec![points.to_vec()],
+        )
+    }
+
+    /// Fills the union of `polygons` as one region, so areas where polygons overlap are
+    /// hachured once with the same scan lines. Only the fill is drawn.
+    pub fn fill_region<F>(
+        &self,
+        polygons: &[Vec<Point2D<F>>],
+        options: &Option<Options>,
+    ) -> Drawable<F>
+    where
+        F: Float + Trig + FromPrimitive + MulAssign + Display,
+    {
+        let mut options = options
+            .clone()
+            .unwrap_or_else(|| self.default_options.clone());
+        let mut paths = vec![];
+        if options.fill_style == Some(FillStyle::Solid) {
+            for polygon in polygons {
+                paths.push(solid_fill_polygon(&vec![polygon.clone()], &mut options));
+            }
+        } else {
+            let union = options.fill_union;
+            options.fill_union = Some(true);
+            paths.push(pattern_fill_polygons(polygons.to_vec(), &mut options));
+            options.fill_union = union;
+        }
+        self.d("fill_region", &paths, &Some(options))
+    }
+
+    /// Draws a rounded rectangle with a triangular tail pointing at `tail_tip`, as one
+    /// outline with a single fill. The tail leaves from the side facing `tail_tip`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn callout<F>(
+        &self,
+        x: F,
+        y: F,
+        width: F,
+        height: F,
+        radius: F,
+        tail_tip: Point2D<F>,
+        options: &Option<Options>,
+    ) -> Drawable<F>
+    where
+        F: Float + Trig + FromPrimitive + MulAssign + Display,
+    {
+        let outline = callout_outline(x, y, width, height, radius, tail_tip);
+        let mut drawable = self.polygon(&outline, options);
+        drawable.shape = "callout".into();
+        drawable
+    }
+
+    pub fn path<F>(&self, d: String, options: &Option<Options>) -> Drawable<F>
+    where
+        F: Float + Trig + FromPrimitive + MulAssign + Display,
+    {
+        let mut options = options.clone().unwrap_or(self.default_options.clone());
+        let mut paths = vec![];
+        if d.is_empty() {
+            self.d("path", &paths, &Some(options))
+        } else {
+            let simplified = options.simplification.map(|a| a < 1.0).unwrap_or(false);
+            let distance = if simplified {
+                _c::<F>(4.0) - _c::<F>(4.0) * _c::<F>(options.simplification.unwrap())
             } else {
                 (_c::<F>(1.0) + _c::<F>(options.roughness.unwrap_or(1.0))) / _c::<F>(2.0)
             };
 
-            let sets = points_on_segments(segments.clone(), Some(_c(1.0)), Some(distance));
+            let sets = points_on_path(d.clone(), Some(_c(1.0)), Some(distance));
             if options.fill.is_some() {
-                if options.fill_style == Some(FillStyle::Solid) {
-                    paths.push(solid_fill_polygon(&sets, &mut options));
+                paths.extend(path_fills(&sets, &mut options));
+            }
+
+            if options.stroke.is_some() {
+                if simplified {
+                    sets.iter()
+                        .for_each(|s| paths.push(linear_path(s, false, &mut options)));
                 } else {
-                    paths.push(pattern_fill_polygons(sets.clone(), &mut options));
+                    paths.push(svg_path(d, &mut options));
                 }
             }
 
+            self.d("path", &paths, &Some(options))
+        }
+    }
+
+    pub fn path_from_segments<F>(
+        &self,
+        segments: Vec<PathSegment>,
+        options: &Option<Options>,
+    ) -> Drawable<F>
+    where
+        F: Float + Trig + FromPrimitive + MulAssign + Display,
+    {
+        let mut options = options.clone().unwrap_or(self.default_options.clone());
+        let mut paths = vec![];
+        if segments.is_empty() {
+            self.d("path", &paths, &Some(options))
+        } else {
+            let simplified = options.simplification.map(|a| a < 1.0).unwrap_or(false);
+            let distance = if simplified {
+                _c::<F>(4.0) - _c::<F>(4.0) * _c::<F>(options.simplification.unwrap())
+            } else {
+                (_c::<F>(1.0) + _c::<F>(options.roughness.unwrap_or(1.0))) / _c::<F>(2.0)
+            };
+
+            let sets = points_on_segments(segments.clone(), Some(_c(1.0)), Some(distance));
+            if options.fill.is_some() {
+                paths.extend(path_fills(&sets, &mut options));
+            }
+
             if options.stroke.is_some() {
                 if simplified {
                     sets.iter()
@@ -460,6 +1112,70 @@ impl Generator {
         }
     }
 
+    /// Roughens the primitives of an svg document. Every `rect`, `circle`, `ellipse`, `line`,
+    /// `polygon`, `polyline` and `path` element becomes one drawable, in document order. The
+    /// `fill`, `stroke` and `stroke-width` attributes and inline style declarations override
+    /// the generator's default options; transforms, style sheets and units other than user
+    /// units are not interpreted. Malformed documents yield no drawables. Needs the
+    /// `svg-import` feature.
+    #[cfg(feature = "svg-import")]
+    pub fn roughen_svg(&self, svg_source: &str) -> Vec<Drawable<f64>> {
+        let mut drawables = vec![];
+        for element in svg_elements(svg_source) {
+            let mut options = self.default_options.clone();
+            if let Some(fill) = element.attribute("fill").and_then(svg_paint) {
+                options.fill = fill;
+            }
+            if let Some(stroke) = element.attribute("stroke").and_then(svg_paint) {
+                options.stroke = stroke;
+            }
+            if let Some(width) = element.attribute("stroke-width").and_then(svg_number) {
+                options.stroke_width = Some(width as f32);
+            }
+            let options = Some(options);
+            let number = |name: &str| element.attribute(name).and_then(svg_number).unwrap_or(0.0);
+            let points = || {
+                PointsParser::from(element.attribute("points").unwrap_or(""))
+                    .map(|(x, y)| Point2D::new(x, y))
+                    .collect::<Vec<_>>()
+            };
+            let drawable = match element.name.as_str() {
+                "rect" => Some(self.rectangle(
+                    number("x"),
+                    number("y"),
+                    number("width"),
+                    number("height"),
+                    &options,
+                )),
+                "circle" => {
+                    Some(self.circle_radius(number("cx"), number("cy"), number("r"), &options))
+                }
+                "ellipse" => Some(self.ellipse(
+                    number("cx"),
+                    number("cy"),
+                    number("rx") * 2.0,
+                    number("ry") * 2.0,
+                    &options,
+                )),
+                "line" => Some(self.line(
+                    number("x1"),
+                    number("y1"),
+                    number("x2"),
+                    number("y2"),
+                    &options,
+                )),
+                "polygon" => Some(self.polygon(&points(), &options)),
+                "polyline" => Some(self.open_path(&points(), &options)),
+                "path" => element
+                    .attribute("d")
+                    .map(|d| self.path(d.to_string(), &options)),
+                _ => None,
+            };
+            drawables.extend(drawable);
+        }
+        drawables
+    }
+
     pub fn ops_to_path<F>(mut drawing: OpSet<F>, fixed_decimals: Option<u32>) -> String
     where
         F: Float + FromPrimitive + Trig + Display,
@@ -476,7 +1192,7 @@ impl Generator {
 
             match item.op {
                 OpType::Move => {
-                    write!(&mut path, "L{} {} ", item.data[0], item.data[1])
+                    write!(&mut path, "M{} {} ", item.data[0], item.data[1])
                         .expect("Failed to write path string");
                 }
                 OpType::BCurveTo => {
@@ -493,7 +1209,7 @@ impl Generator {
                     .expect("Failed to write path string");
                 }
                 OpType::LineTo => {
-                    write!(&mut path, "L{} {}, ", item.data[0], item.data[1])
+                    write!(&mut path, "L{} {} ", item.data[0], item.data[1])
                         .expect("Failed to write path string");
                 }
             }
@@ -510,6 +1226,7 @@ impl Generator {
         let o = drawable.options;
         let mut path_infos = vec![];
         for drawing in sets.iter() {
+            let drawing = &export_opset(&drawable.shape, drawing, &o);
             let path_info = match drawing.op_set_type {
                 OpSetType::Path => PathInfo {
                     d: Self::ops_to_path(drawing.clone(), None),
@@ -542,3 +1259,1339 @@ impl Generator {
         path_infos
     }
 }
+
+/// Fallible variants of the shape methods. They validate that coordinates are finite and
+/// sizes are non-negative before drawing.
+impl Generator {
+    pub fn try_line<F>(
+        &self,
+        x1: F,
+        y1: F,
+        x2: F,
+        y2: F,
+        options: &Option<Options>,
+    ) -> Result<Drawable<F>, RoughrError>
+    where
+        F: Float + Trig + FromPrimitive,
+    {
+        check_finite(&[x1, y1, x2, y2])?;
+        Ok(self.line(x1, y1, x2, y2, options))
+    }
+
+    pub fn try_rectangle<F>(
+        &self,
+        x: F,
+        y: F,
+        width: F,
+        height: F,
+        options: &Option<Options>,
+    ) -> Result<Drawable<F>, RoughrError>
+    where
+        F: Float + Trig + FromPrimitive,
+    {
+        check_finite(&[x, y])?;
+        check_size(&[width, height])?;
+        Ok(self.rectangle(x, y, width, height, options))
+    }
+
+    pub fn try_ellipse<F>(
+        &self,
+        x: F,
+        y: F,
+        width: F,
+        height: F,
+        options: &Option<Options>,
+    ) -> Result<Drawable<F>, RoughrError>
+    where
+        F: Float + Trig + FromPrimitive,
+    {
+        check_finite(&[x, y])?;
+        check_size(&[width, height])?;
+        Ok(self.ellipse(x, y, width, height, options))
+    }
+
+    pub fn try_circle<F>(
+        &self,
+        x: F,
+        y: F,
+        diameter: F,
+        options: &Option<Options>,
+    ) -> Result<Drawable<F>, RoughrError>
+    where
+        F: Float + Trig + FromPrimitive,
+    {
+        check_finite(&[x, y])?;
+        check_size(&[diameter])?;
+        Ok(self.circle(x, y, diameter, options))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_arc<F>(
+        &self,
+        x: F,
+        y: F,
+        width: F,
+        height: F,
+        start: F,
+        stop: F,
+        closed: bool,
+        options: &Option<Options>,
+    ) -> Result<Drawable<F>, RoughrError>
+    where
+        F: Float + Trig + FromPrimitive,
+    {
+        check_finite(&[x, y, start, stop])?;
+        check_size(&[width, height])?;
+        Ok(self.arc(x, y, width, height, start, stop, closed, options))
+    }
+
+    pub fn try_linear_path<F>(
+        &self,
+        points: &[Point2D<F>],
+        close: bool,
+        options: &Option<Options>,
+    ) -> Result<Drawable<F>, RoughrError>
+    where
+        F: Float + Trig + FromPrimitive,
+    {
+        check_finite(&points.iter().flat_map(|p| [p.x, p.y]).collect::<Vec<_>>())?;
+        Ok(self.linear_path(points, close, options))
+    }
+
+    pub fn try_polygon<F>(
+        &self,
+        points: &[Point2D<F>],
+        options: &Option<Options>,
+    ) -> Result<Drawable<F>, RoughrError>
+    where
+        F: Float + Trig + FromPrimitive + MulAssign + Display,
+    {
+        check_finite(&points.iter().flat_map(|p| [p.x, p.y]).collect::<Vec<_>>())?;
+        Ok(self.polygon(points, options))
+    }
+
+    pub fn try_curve<F>(
+        &self,
+        points: &[Point2D<F>],
+        options: &Option<Options>,
+    ) -> Result<Drawable<F>, RoughrError>
+    where
+        F: Float + Trig + FromPrimitive + MulAssign + Display,
+    {
+        check_finite(&points.iter().flat_map(|p| [p.x, p.y]).collect::<Vec<_>>())?;
+        Ok(self.curve(points, options))
+    }
+
+    /// Draws a polygon from an svg `points` attribute, such as `"0,0 10,0 10,10"`.
+    /// Coordinates may be separated by whitespace and commas.
+    pub fn polygon_from_str<F>(
+        &self,
+        points: &str,
+        options: &Option<Options>,
+    ) -> Result<Drawable<F>, RoughrError>
+    where
+        F: Float + Trig + FromPrimitive + MulAssign + Display,
+    {
+        let coordinates = points
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|token| !token.is_empty())
+            .map(|token| {
+                token
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(F::from_f64)
+                    .ok_or(RoughrError::InvalidPoints)
+            })
+            .collect::<Result<Vec<F>, _>>()?;
+        if coordinates.len() % 2 == 1 {
+            return Err(RoughrError::InvalidPoints);
+        }
+        let points: Vec<Point2D<F>> = coordinates
+            .chunks(2)
+            .map(|c| Point2D::new(c[0], c[1]))
+            .collect();
+        self.try_polygon(&points, options)
+    }
+}
+
+/// Outline of a rounded rectangle, clockwise from its top left corner, with a tail
+/// inserted on the side facing `tip`.
+fn callout_outline<F: Float + Trig + FromPrimitive>(
+    x: F,
+    y: F,
+    width: F,
+    height: F,
+    radius: F,
+    tip: Point2D<F>,
+) -> Vec<Point2D<F>> {
+    let r = radius.max(F::zero()).min(width.min(height) / _c(2.0));
+    let center = Point2D::new(x + width / _c(2.0), y + height / _c(2.0));
+    let tail_side = if (tip.x - center.x).abs() * height > (tip.y - center.y).abs() * width {
+        if tip.x > center.x {
+            1
+        } else {
+            3
+        }
+    } else if tip.y < center.y {
+        0
+    } else {
+        2
+    };
+    // edge that leads into each corner, followed by the corner center and its start angle
+    let sides = [
+        (
+            Point2D::new(x + r, y),
+            Point2D::new(x + width - r, y),
+            Point2D::new(x + width - r, y + r),
+            -90.0,
+        ),
+        (
+            Point2D::new(x + width, y + r),
+            Point2D::new(x + width, y + height - r),
+            Point2D::new(x + width - r, y + height - r),
+            0.0,
+        ),
+        (
+            Point2D::new(x + width - r, y + height),
+            Point2D::new(x + r, y + height),
+            Point2D::new(x + r, y + height - r),
+            90.0,
+        ),
+        (
+            Point2D::new(x, y + height - r),
+            Point2D::new(x, y + r),
+            Point2D::new(x + r, y + r),
+            180.0,
+        ),
+    ];
+
+    let mut outline = vec![];
+    for (side, (from, to, corner, start_angle)) in sides.into_iter().enumerate() {
+        if side == tail_side {
+            let length = (to - from).length();
+            if length > F::zero() {
+                let direction = (to - from) / length;
+                let half_base = (length / _c(2.0)).min(width.min(height) / _c(6.0));
+                let along = (tip - from)
+                    .dot(direction)
+                    .max(half_base)
+                    .min(length - half_base);
+                outline.push(from + direction * (along - half_base));
+                outline.push(tip);
+                outline.push(from + direction * (along + half_base));
+            } else {
+                outline.push(tip);
+            }
+        }
+        for step in 0..=4 {
+            let angle: F = _c::<F>(start_angle + 22.5 * step as f32).to_radians();
+            outline.push(corner + Vector2D::new(Float::cos(angle), Float::sin(angle)) * r);
+        }
+    }
+    outline
+}
+
+/// Start points of the first stroke pass of each of the `count` segments of a linear path,
+/// or `None` when the ops don't have one or two passes per segment.
+fn first_pass_starts<F: Float + Trig>(ops: &[Op<F>], count: usize) -> Option<Vec<Point2D<F>>> {
+    let starts: Vec<Point2D<F>> = ops
+        .iter()
+        .filter(|op| op.op == OpType::Move)
+        .map(|op| Point2D::new(op.data[0], op.data[1]))
+        .collect();
+    if starts.len() == count {
+        Some(starts)
+    } else if starts.len() == count * 2 {
+        Some(starts.into_iter().step_by(2).collect())
+    } else {
+        None
+    }
+}
+
+/// Op set as it should be exported. Ellipse and circle outlines are redrawn with
+/// `ellipse_export_segments` cubic segments per stroke pass when that is set.
+pub(crate) fn export_opset<F>(shape: &str, set: &OpSet<F>, o: &Options) -> OpSet<F>
+where
+    F: Float + Trig + FromPrimitive,
+{
+    let segments = match o.ellipse_export_segments {
+        Some(segments) if segments > 0 && matches!(shape, "ellipse" | "circle") => segments,
+        _ => return set.clone(),
+    };
+    if set.op_set_type == OpSetType::FillSketch {
+        return set.clone();
+    }
+    // end points of every pass, each pass starting at a move
+    let mut passes: Vec<Vec<Point2D<F>>> = vec![];
+    for op in set.ops.iter() {
+        let end = Point2D::new(op.data[op.data.len() - 2], op.data[op.data.len() - 1]);
+        match (op.op.clone(), passes.last_mut()) {
+            (OpType::Move, _) | (_, None) => passes.push(vec![end]),
+            (_, Some(pass)) => pass.push(end),
+        }
+    }
+    let mut ops = vec![];
+    for pass in passes {
+        let count = segments as usize;
+        if pass.len() <= count + 1 {
+            return set.clone();
+        }
+        let last = pass.len() - 1;
+        let samples: Vec<Point2D<F>> = (0..=count).map(|i| pass[i * last / count]).collect();
+        let bezier = catmull_rom_to_bezier(&samples, _c(0.5));
+        ops.push(Op {
+            op: OpType::Move,
+            data: vec![bezier[0].x, bezier[0].y],
+        });
+        for curve in bezier[1..].chunks(3) {
+            ops.push(Op {
+                op: OpType::BCurveTo,
+                data: vec![
+                    curve[0].x, curve[0].y, curve[1].x, curve[1].y, curve[2].x, curve[2].y,
+                ],
+            });
+        }
+    }
+    OpSet {
+        op_set_type: set.op_set_type.clone(),
+        ops,
+        size: set.size,
+        path: set.path.clone(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use euclid::default::Point2D;
+    use euclid::point2;
+
+    use super::Generator;
+    use crate::core::{Drawable, FillStyle, OpSetType, OpType, OptionsBuilder};
+    use crate::error::RoughrError;
+    use crate::Srgba;
+
+    #[test]
+    fn rectangle_detailed_returns_fill_polygon() {
+        // the fill follows the jittered outline corners, so turn the jitter off here
+        let options = OptionsBuilder::default()
+            .deterministic_preview(true)
+            .fill(Srgba::new(1.0, 0.0, 0.0, 1.0))
+            .fill_style(FillStyle::Hachure)
+            .build()
+            .unwrap();
+        let (drawable, polygons) =
+            Generator::default().rectangle_detailed(10.0f32, 20.0, 30.0, 40.0, &Some(options));
+        assert_eq!(drawable.shape, "rectangle");
+        assert_eq!(drawable.sets.len(), 2);
+        assert_eq!(
+            polygons,
+            vec![vec![
+                point2(10.0, 20.0),
+                point2(40.0, 20.0),
+                point2(40.0, 60.0),
+                point2(10.0, 60.0),
+            ]]
+        );
+    }
+
+    #[test]
+    fn circle_radius_matches_circle_diameter() {
+        let generator = Generator::default();
+        let by_radius = generator.circle_radius(50.0f32, 50.0, 20.0, &None);
+        let by_diameter = generator.circle(50.0f32, 50.0, 40.0, &None);
+        assert_eq!(by_radius.shape, "circle");
+        assert_eq!(by_radius.sets, by_diameter.sets);
+    }
+
+    #[test]
+    fn debug_bbox_matches_bounds() {
+        let generator = Generator::default();
+        let drawable = generator.ellipse(50.0f32, 40.0, 60.0, 30.0, &None);
+        let bounds = drawable.bounds().unwrap();
+        let exact = OptionsBuilder::default()
+            .roughness(0.0)
+            .disable_multi_stroke(true)
+            .build()
+            .unwrap();
+        let debug = drawable.debug_bbox_drawable(&Some(exact));
+        assert_eq!(debug.shape, "rectangle");
+        let corners: Vec<_> = debug.sets[0]
+            .ops
+            .iter()
+            .filter(|op| op.op == OpType::Move)
+            .map(|op| point2(op.data[0], op.data[1]))
+            .collect();
+        let expected = [
+            bounds.min,
+            point2(bounds.max.x, bounds.min.y),
+            bounds.max,
+            point2(bounds.min.x, bounds.max.y),
+        ];
+        assert_eq!(corners.len(), expected.len());
+        // the rectangle is rebuilt from min and size, so far corners may be off by rounding
+        for (corner, expected) in corners.iter().zip(expected.iter()) {
+            assert!((*corner - *expected).length() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn snap_to_grid_rounds_to_step() {
+        let drawable = Generator::default().ellipse(50.0f32, 40.0, 60.0, 30.0, &None);
+        let snapped = drawable.snap_to_grid(5.0);
+        assert_eq!(snapped.sets.len(), drawable.sets.len());
+        assert!(snapped
+            .sets
+            .iter()
+            .flat_map(|set| set.ops.iter())
+            .flat_map(|op| op.data.iter())
+            .all(|v| v % 5.0 == 0.0));
+    }
+
+    #[test]
+    fn triangle_line_segments_per_pass() {
+        let triangle = [point2(0.0f32, 0.0), point2(100.0, 0.0), point2(50.0, 80.0)];
+        let drawable = Generator::default().polygon(&triangle, &None);
+        let segments = drawable.line_segments(0.5);
+        let groups = 1 + segments.windows(2).filter(|w| w[0].1 != w[1].0).count();
+        // every edge is drawn in two passes
+        assert_eq!(groups, 3 * 2);
+    }
+
+    #[test]
+    fn smooth_circle_exports_arc_commands() {
+        let smooth = OptionsBuilder::default().roughness(0.0).build().unwrap();
+        let path = Generator::default().circle_svg_path(50.0f32, 50.0, 20.0, &Some(smooth));
+        assert_eq!(path, "M60 50 A10 10 0 1 0 40 50 A10 10 0 1 0 60 50 Z");
+
+        let rough = Generator::default().circle_svg_path(50.0f32, 50.0, 20.0, &None);
+        assert!(!rough.contains('A'));
+        assert!(rough.contains('C'));
+    }
+
+    #[test]
+    fn clip_hachure_fill_to_square() {
+        let options = OptionsBuilder::default()
+            .fill(Srgba::new(1.0, 0.0, 0.0, 1.0))
+            .fill_style(FillStyle::Hachure)
+            .build()
+            .unwrap();
+        let drawable = Generator::default().rectangle(0.0f32, 0.0, 200.0, 200.0, &Some(options));
+        let square = [
+            point2(50.0, 50.0),
+            point2(80.0, 50.0),
+            point2(80.0, 80.0),
+            point2(50.0, 80.0),
+        ];
+        let clipped = drawable.clip_to_polygon(&square);
+
+        let fill = &clipped.sets[0];
+        assert!(!fill.ops.is_empty());
+        assert!(clipped
+            .sets
+            .iter()
+            .flat_map(|set| set.ops.iter())
+            .all(|op| (50.0 - 1e-3..=80.0 + 1e-3).contains(&op.data[0])
+                && (50.0 - 1e-3..=80.0 + 1e-3).contains(&op.data[1])));
+    }
+
+    #[test]
+    fn wavy_line_oscillates_around_baseline() {
+        let drawable = Generator::default().wavy_line(
+            point2(0.0f32, 0.0),
+            point2(200.0, 0.0),
+            4.0,
+            20.0,
+            &None,
+        );
+        let ys: Vec<f32> = drawable.sets[0]
+            .ops
+            .iter()
+            .filter(|op| op.op == OpType::BCurveTo)
+            .map(|op| op.data[5])
+            .collect();
+        let crossings = ys
+            .windows(2)
+            .filter(|w| w[0].signum() != w[1].signum())
+            .count();
+        assert!(crossings >= 10);
+    }
+
+    #[test]
+    fn wavy_line_without_wavelength_is_straight_line() {
+        for wavelength in [0.0f32, -5.0, f32::NAN, f32::INFINITY] {
+            let drawable = Generator::default().wavy_line(
+                point2(0.0f32, 0.0),
+                point2(100.0, 0.0),
+                4.0,
+                wavelength,
+                &None,
+            );
+            assert_eq!(drawable.shape, "line");
+            assert!(drawable.sets[0]
+                .ops
+                .iter()
+                .all(|op| op.data.iter().all(|d| d.is_finite())));
+        }
+    }
+
+    #[test]
+    fn wavy_line_bounds_points_for_tiny_wavelength() {
+        let drawable = Generator::default().wavy_line(
+            point2(0.0f32, 0.0),
+            point2(100.0, 0.0),
+            4.0,
+            1e-6,
+            &None,
+        );
+        let ops = &drawable.sets[0].ops;
+        assert!(ops.len() < 10_000);
+        assert!(ops.iter().all(|op| op.data.iter().all(|d| d.is_finite())));
+    }
+
+    #[test]
+    fn open_path_skips_closing_segment() {
+        let generator = Generator::default();
+        let points = [
+            point2(0.0f32, 0.0),
+            point2(100.0, 0.0),
+            point2(100.0, 100.0),
+        ];
+        let open = generator.open_path(&points, &None);
+        let closed = generator.polygon(&points, &None);
+
+        let moves = |d: &crate::core::Drawable<f32>| {
+            d.sets[0]
+                .ops
+                .iter()
+                .filter(|op| op.op == OpType::Move)
+                .count()
+        };
+        // two passes per edge
+        assert_eq!(moves(&open), 2 * 2);
+        assert_eq!(moves(&closed), 3 * 2);
+        let last = open.sets[0].ops.last().unwrap();
+        assert!((last.data[4] - 100.0).abs() < 5.0 && (last.data[5] - 100.0).abs() < 5.0);
+    }
+
+    #[test]
+    #[cfg(feature = "svg-import")]
+    fn roughen_svg_rect_and_circle() {
+        let svg = r##"<?xml version="1.0"?>
+            <svg xmlns="http://www.w3.org/2000/svg" width="100" height="100">
+                <!-- <rect x="0" y="0" width="1" height="1"/> -->
+                <rect x="10" y="10" width="30" height="20" fill="red"/>
+                <circle cx="70" cy="70" r="15" stroke="none" fill="#00ff00"></circle>
+            </svg>"##;
+        let drawables = Generator::default().roughen_svg(svg);
+
+        assert_eq!(drawables.len(), 2);
+        assert_eq!(drawables[0].shape, "rectangle");
+        assert_eq!(
+            drawables[0].options.fill,
+            Some(Srgba::new(1.0, 0.0, 0.0, 1.0))
+        );
+        assert!(drawables[0].options.stroke.is_some());
+        assert_eq!(drawables[1].shape, "circle");
+        assert_eq!(drawables[1].options.stroke, None);
+        assert_eq!(
+            drawables[1].options.fill,
+            Some(Srgba::new(0.0, 1.0, 0.0, 1.0))
+        );
+    }
+
+    #[test]
+    fn animate_frame_loops_seamlessly() {
+        let generator = Generator::default();
+        let frame = |t: f32| {
+            let mut options = OptionsBuilder::default().build().unwrap();
+            options.animate_frame(t);
+            generator
+                .line(0.0f32, 0.0, 100.0, 50.0, &Some(options))
+                .sets
+        };
+        assert_eq!(frame(0.0), frame(1.0));
+        assert_eq!(frame(0.25), frame(1.25));
+
+        let distance = |a: f32, b: f32| {
+            let (a, b) = (frame(a), frame(b));
+            a[0].ops
+                .iter()
+                .zip(b[0].ops.iter())
+                .flat_map(|(p, q)| p.data.iter().zip(q.data.iter()))
+                .fold(0.0f32, |max, (x, y)| max.max((x - y).abs()))
+        };
+        assert!(distance(0.3, 0.301) < 0.1);
+        assert!(distance(0.0, 0.5) > 0.5);
+    }
+
+    #[test]
+    fn animate_frame_keeps_spread_mid_loop() {
+        let spread = |t: f32| {
+            let mut options = OptionsBuilder::default().build().unwrap();
+            options.animate_frame(t);
+            let values: Vec<f64> = (0..20_000).map(|_| options.random()).collect();
+            let mean = values.iter().sum::<f64>() / values.len() as f64;
+            values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64
+        };
+        let (start, middle) = (spread(0.0), spread(0.5));
+        assert!(
+            (middle / start - 1.0).abs() < 0.05,
+            "{} vs {}",
+            middle,
+            start
+        );
+    }
+
+    #[test]
+    fn try_shapes_reject_invalid_input() {
+        let generator = Generator::default();
+        assert_eq!(
+            generator
+                .try_rectangle(0.0f32, 0.0, f32::NAN, 10.0, &None)
+                .err(),
+            Some(RoughrError::NonFiniteInput)
+        );
+        assert_eq!(
+            generator.try_circle(0.0f32, 0.0, -1.0, &None).err(),
+            Some(RoughrError::NegativeSize)
+        );
+        assert_eq!(
+            generator
+                .try_polygon(&[point2(0.0f32, 0.0), point2(f32::INFINITY, 1.0)], &None)
+                .err(),
+            Some(RoughrError::NonFiniteInput)
+        );
+        assert!(generator
+            .try_rectangle(0.0f32, 0.0, 10.0, 10.0, &None)
+            .is_ok());
+    }
+
+    #[test]
+    fn circle_batch_matches_single_circles() {
+        let generator = Generator::default();
+        let centers = [
+            point2(10.0f32, 10.0),
+            point2(50.0, 10.0),
+            point2(90.0, 30.0),
+        ];
+        let options = OptionsBuilder::default().seed(100_u64).build().unwrap();
+        let batch = generator.circle_batch(&centers, 8.0, &Some(options));
+
+        assert_eq!(batch.len(), centers.len());
+        for (i, (drawable, center)) in batch.iter().zip(centers.iter()).enumerate() {
+            let single_options = OptionsBuilder::default()
+                .seed(100 + i as u64)
+                .build()
+                .unwrap();
+            let single = generator.circle_radius(center.x, center.y, 8.0, &Some(single_options));
+            assert_eq!(drawable.shape, "circle");
+            assert_eq!(drawable.sets, single.sets);
+        }
+    }
+
+    #[test]
+    fn filled_rectangle_svg_group() {
+        let options = OptionsBuilder::default()
+            .fill(Srgba::new(1.0, 0.0, 0.0, 1.0))
+            .fill_style(FillStyle::Solid)
+            .build()
+            .unwrap();
+        let drawable = Generator::default().rectangle(10.0f32, 10.0, 30.0, 20.0, &Some(options));
+        let group = drawable.to_svg_group(2.0);
+
+        assert!(group.starts_with("<g>") && group.ends_with("</g>"));
+        assert!(group.contains(r#"fill="rgba(255, 0, 0, 1)" stroke="none""#));
+        assert!(group.contains(r#"fill="none" stroke="rgba(0, 0, 0, 1)" stroke-width="2""#));
+        assert!(group.contains(r#"d="M"#));
+    }
+
+    #[test]
+    fn svg_group_with_transform_keeps_path_data() {
+        let drawable = Generator::default().rectangle(10.0f32, 10.0, 30.0, 20.0, &None);
+        let plain = drawable.to_svg_group(2.0);
+        let moved = drawable.to_svg_group_with_transform(2.0, [2.0, 0.0, 0.0, 2.0, 5.0, -5.0]);
+
+        assert!(moved.starts_with(r#"<g transform="matrix(2 0 0 2 5 -5)">"#));
+        assert_eq!(
+            moved.trim_start_matches(r#"<g transform="matrix(2 0 0 2 5 -5)">"#),
+            plain.trim_start_matches("<g>")
+        );
+    }
+
+    #[test]
+    fn highlight_ellipse_is_open() {
+        fn endpoints(drawable: &Drawable<f32>) -> (Point2D<f32>, Point2D<f32>) {
+            let ops = &drawable.sets[0].ops;
+            let first = &ops[0].data;
+            let last = &ops[ops.len() - 1].data;
+            (
+                Point2D::new(first[0], first[1]),
+                Point2D::new(last[last.len() - 2], last[last.len() - 1]),
+            )
+        }
+        let generator = Generator::default();
+        let smooth = OptionsBuilder::default()
+            .roughness(0.0)
+            .seed(3_u64)
+            .build()
+            .unwrap();
+
+        let (start, end) =
+            endpoints(&generator.ellipse(50.0, 50.0, 40.0, 30.0, &Some(smooth.clone())));
+        assert!((start - end).length() < 1e-3);
+
+        let highlight = generator.highlight_ellipse(50.0, 50.0, 20.0, 15.0, &Some(smooth));
+        assert_eq!(highlight.sets.len(), 1);
+        let (start, end) = endpoints(&highlight);
+        assert!((start - end).length() > 1.0);
+    }
+
+    #[test]
+    fn callout_includes_body_and_tail() {
+        let options = OptionsBuilder::default()
+            .roughness(0.0)
+            .disable_multi_stroke(true)
+            .fill(Srgba::new(1.0, 1.0, 0.8, 1.0))
+            .build()
+            .unwrap();
+        let tip = point2(40.0f32, 90.0);
+        let drawable =
+            Generator::default().callout(10.0f32, 10.0, 80.0, 50.0, 8.0, tip, &Some(options));
+
+        assert_eq!(drawable.shape, "callout");
+        let fills = drawable
+            .sets
+            .iter()
+            .filter(|set| set.op_set_type == OpSetType::FillSketch)
+            .count();
+        assert_eq!(fills, 1);
+        let outline = drawable
+            .sets
+            .iter()
+            .find(|set| set.op_set_type == OpSetType::Path)
+            .unwrap();
+        let touches = |p: Point2D<f32>| {
+            outline.ops.iter().any(|op| {
+                op.data
+                    .chunks(2)
+                    .any(|c| (point2(c[0], c[1]) - p).length() < 1e-3)
+            })
+        };
+        assert!(touches(tip));
+        for corner in [
+            point2(18.0, 10.0),
+            point2(90.0, 18.0),
+            point2(82.0, 60.0),
+            point2(10.0, 52.0),
+        ] {
+            assert!(touches(corner), "{:?}", corner);
+        }
+    }
+
+    #[test]
+    fn dashed_path_splits_into_dashes() {
+        let options = OptionsBuilder::default()
+            .disable_multi_stroke(true)
+            .build()
+            .unwrap();
+        let points = [point2(0.0f32, 0.0), point2(100.0, 0.0)];
+        let drawable = Generator::default().dashed_path(&points, 10.0, 5.0, &Some(options));
+
+        assert_eq!(drawable.sets.len(), 1);
+        let moves: Vec<_> = drawable.sets[0]
+            .ops
+            .iter()
+            .filter(|op| op.op == OpType::Move)
+            .collect();
+        assert_eq!(moves.len(), 7);
+    }
+
+    #[test]
+    fn fill_roughness_only_affects_fill() {
+        // largest distance of a curve control point from the straight line it bends
+        fn max_jitter(ops: &[crate::core::Op<f32>]) -> f32 {
+            let mut start: Point2D<f32> = point2(0.0, 0.0);
+            let mut jitter: f32 = 0.0;
+            for op in ops {
+                if op.op == OpType::Move {
+                    start = point2(op.data[0], op.data[1]);
+                } else if op.op == OpType::BCurveTo {
+                    let end = point2(op.data[4], op.data[5]);
+                    let direction = (end - start).normalize();
+                    for control in [
+                        point2(op.data[0], op.data[1]),
+                        point2(op.data[2], op.data[3]),
+                    ] {
+                        jitter = jitter.max(direction.cross(control - start).abs());
+                    }
+                    start = end;
+                }
+            }
+            jitter
+        }
+        let rectangle = |fill_roughness: Option<f32>| {
+            let mut builder = OptionsBuilder::default();
+            builder
+                .roughness(0.0)
+                .fill(Srgba::new(0.0, 0.0, 1.0, 1.0))
+                .fill_style(FillStyle::Hachure);
+            if let Some(r) = fill_roughness {
+                builder.fill_roughness(r);
+            }
+            let options = builder.build().unwrap();
+            Generator::default().rectangle(10.0f32, 10.0, 80.0, 60.0, &Some(options))
+        };
+        let crisp = rectangle(None);
+        let loose = rectangle(Some(3.0));
+
+        assert_eq!(crisp.sets[1].ops, loose.sets[1].ops);
+        assert!(max_jitter(&crisp.sets[0].ops) < 1e-3);
+        assert!(max_jitter(&loose.sets[0].ops) > 0.5);
+    }
+
+    #[test]
+    fn zero_size_shapes_are_finite() {
+        let all_finite = |drawable: &Drawable<f32>| {
+            drawable.sets.iter().all(|set| {
+                set.ops
+                    .iter()
+                    .all(|op| op.data.iter().all(|v| v.is_finite()))
+            })
+        };
+        let filled = OptionsBuilder::default()
+            .fill(Srgba::new(0.0, 0.0, 1.0, 1.0))
+            .build()
+            .unwrap();
+        let generator = Generator::default();
+
+        let thin = generator.rectangle(5.0f32, 5.0, 0.0, 10.0, &Some(filled.clone()));
+        assert!(all_finite(&thin));
+        assert_eq!(thin.sets.len(), 1);
+        assert_eq!(thin.sets[0].op_set_type, OpSetType::Path);
+
+        let point = generator.rectangle(5.0f32, 5.0, 0.0, 0.0, &Some(filled.clone()));
+        assert!(point.sets.is_empty());
+        let line = generator.line(5.0f32, 5.0, 5.0, 5.0, &Some(filled.clone()));
+        assert!(line.sets.is_empty());
+
+        let mut dotted = filled;
+        dotted.zero_size_dot = Some(true);
+        let line = generator.line(5.0f32, 5.0, 5.0, 5.0, &Some(dotted));
+        assert!(all_finite(&line));
+        assert_eq!(line.sets.len(), 1);
+        assert_eq!(line.sets[0].ops.len(), 2);
+        assert!((line.sets[0].ops[0].data[0] - 5.0).abs() <= 1.0);
+    }
+
+    #[test]
+    fn fill_subpaths_separately_splits_fills() {
+        let fills = |separately: bool, fill_style: FillStyle| {
+            let options = OptionsBuilder::default()
+                .fill(Srgba::new(0.0, 0.5, 0.0, 1.0))
+                .fill_style(fill_style)
+                .fill_subpaths_separately(separately)
+                .build()
+                .unwrap();
+            let drawable: Drawable<f32> = Generator::default().path(
+                "M0 0 L20 0 L20 20 L0 20 Z M50 0 L70 0 L70 20 L50 20 Z".into(),
+                &Some(options),
+            );
+            drawable
+                .sets
+                .iter()
+                .filter(|set| set.op_set_type != OpSetType::Path)
+                .map(|set| set.op_set_type.clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(fills(false, FillStyle::Solid), vec![OpSetType::FillPath]);
+        assert_eq!(
+            fills(true, FillStyle::Solid),
+            vec![OpSetType::FillPath, OpSetType::FillPath]
+        );
+        assert_eq!(
+            fills(false, FillStyle::Hachure),
+            vec![OpSetType::FillSketch]
+        );
+        assert_eq!(
+            fills(true, FillStyle::Hachure),
+            vec![OpSetType::FillSketch, OpSetType::FillSketch]
+        );
+    }
+
+    #[test]
+    fn jittered_grid_stays_within_jitter() {
+        let options = OptionsBuilder::default()
+            .roughness(0.0)
+            .disable_multi_stroke(true)
+            .build()
+            .unwrap();
+        let grid =
+            Generator::default().jittered_grid(0.0f32, 0.0, 100.0, 60.0, 4, 3, 2.0, &Some(options));
+
+        let starts: Vec<Point2D<f32>> = grid.sets[0]
+            .ops
+            .iter()
+            .filter(|op| op.op == OpType::Move)
+            .map(|op| point2(op.data[0], op.data[1]))
+            .collect();
+        assert_eq!(starts.len(), 5 + 4);
+        let (columns, rows) = starts.split_at(5);
+        let offsets: Vec<f32> = columns
+            .iter()
+            .enumerate()
+            .map(|(i, p)| p.x - 25.0 * i as f32)
+            .chain(rows.iter().enumerate().map(|(i, p)| p.y - 20.0 * i as f32))
+            .collect();
+        assert!(offsets.iter().all(|d| d.abs() <= 2.0));
+        assert_eq!(
+            (offsets[0], offsets[4], offsets[5], offsets[8]),
+            (0.0, 0.0, 0.0, 0.0)
+        );
+        assert!(offsets.iter().any(|d| d.abs() > 0.01));
+    }
+
+    #[test]
+    fn frame_is_inset_from_edges() {
+        let options = OptionsBuilder::default().roughness(0.0).build().unwrap();
+        let generator = Generator::default();
+        let frame = generator.frame(200.0f32, 100.0, 10.0, &Some(options.clone()));
+        assert_eq!(frame.shape, "frame");
+        let bounds = frame.bounds().unwrap();
+        assert_eq!(bounds.min, point2(10.0, 10.0));
+        assert_eq!(bounds.max, point2(190.0, 90.0));
+
+        let double = generator.double_frame(200.0f32, 100.0, 10.0, 4.0, &Some(options));
+        assert_eq!(double.sets.len(), 2);
+        assert_eq!(double.bounds(), Some(bounds));
+    }
+
+    #[test]
+    fn linear_path_weighted_pins_zero_weight_vertices() {
+        let points = [
+            (point2(0.0f32, 0.0), 1.0),
+            (point2(100.0, 0.0), 0.0),
+            (point2(100.0, 100.0), 1.0),
+        ];
+        let drawable = Generator::default().linear_path_weighted(&points, false, &None);
+        let ops = &drawable.sets[0].ops;
+        // two passes over two segments
+        assert_eq!(ops.len(), 8);
+
+        let pinned = points[1].0;
+        assert_eq!(point2(ops[1].data[4], ops[1].data[5]), pinned);
+        assert_eq!(point2(ops[3].data[4], ops[3].data[5]), pinned);
+        assert_eq!(point2(ops[4].data[0], ops[4].data[1]), pinned);
+        assert_eq!(point2(ops[6].data[0], ops[6].data[1]), pinned);
+
+        assert_ne!(point2(ops[0].data[0], ops[0].data[1]), points[0].0);
+        assert_ne!(point2(ops[5].data[4], ops[5].data[5]), points[2].0);
+    }
+
+    #[test]
+    fn dimension_line_has_shaft_heads_and_ticks() {
+        let options = OptionsBuilder::default()
+            .deterministic_preview(true)
+            .disable_multi_stroke(true)
+            .build()
+            .unwrap();
+        let (from, to) = (point2(10.0f32, 20.0), point2(110.0, 20.0));
+        let drawable = Generator::default().dimension_line(from, to, 8.0, &Some(options));
+        assert_eq!(drawable.shape, "dimension_line");
+        assert_eq!(drawable.sets.len(), 5);
+
+        let ends = |i: usize| {
+            let ops = &drawable.sets[i].ops;
+            let last = &ops[ops.len() - 1].data;
+            (
+                point2(ops[0].data[0], ops[0].data[1]),
+                point2(last[4], last[5]),
+            )
+        };
+        assert_eq!(ends(0), (from, to));
+        // arrowheads are open V shapes with their tip on the shaft end
+        for (i, tip) in [(1, from), (2, to)] {
+            let ops = &drawable.sets[i].ops;
+            assert_eq!(ops.len(), 4);
+            assert_eq!(point2(ops[1].data[4], ops[1].data[5]), tip);
+            let (start, end) = ends(i);
+            assert!((start.x - tip.x).abs() > 1.0 && start.x == end.x);
+        }
+        for (i, end) in [(3, from), (4, to)] {
+            let (a, b) = ends(i);
+            assert_eq!((a.x, b.x), (end.x, end.x));
+            assert_eq!((a.y - b.y).abs(), 8.0);
+        }
+    }
+
+    #[test]
+    fn polygon_from_str_parses_points() {
+        let generator = Generator::default();
+        let triangle: Drawable<f32> = generator.polygon_from_str("0,0 10,0 10,10", &None).unwrap();
+        assert_eq!(triangle.shape, "polygon");
+        let moves: Vec<_> = triangle.sets[0]
+            .ops
+            .iter()
+            .filter(|op| op.op == OpType::Move)
+            .collect();
+        // three closed edges, each drawn twice
+        assert_eq!(moves.len(), 6);
+
+        assert_eq!(
+            generator.polygon_from_str::<f32>("0,0 10", &None).err(),
+            Some(RoughrError::InvalidPoints)
+        );
+        assert_eq!(
+            generator
+                .polygon_from_str::<f32>("0,0 10,x 5,5", &None)
+                .err(),
+            Some(RoughrError::InvalidPoints)
+        );
+    }
+
+    #[test]
+    fn ellipse_export_segments_shrinks_exported_path() {
+        let commands = |segments: Option<u32>| {
+            let mut options = OptionsBuilder::default().build().unwrap();
+            options.ellipse_export_segments = segments;
+            let drawable =
+                Generator::default().ellipse(100.0f32, 100.0, 150.0, 80.0, &Some(options));
+            let sets = drawable.sets.clone();
+            let d = Generator::to_paths(drawable).remove(0).d;
+            (sets, d.matches('C').count(), d.matches('M').count())
+        };
+        let (full_sets, full_curves, full_moves) = commands(None);
+        let (reduced_sets, reduced_curves, reduced_moves) = commands(Some(4));
+
+        assert_eq!(full_sets, reduced_sets);
+        assert_eq!(full_moves, 2);
+        assert_eq!(reduced_moves, 2);
+        assert_eq!(reduced_curves, 8);
+        assert!(full_curves > reduced_curves);
+    }
+
+    #[test]
+    fn dash_pattern_sets_all_dash_options() {
+        let options = OptionsBuilder::default()
+            .dash_pattern(6.0, 3.0, 1.5)
+            .build()
+            .unwrap();
+        assert_eq!(options.stroke_line_dash, Some(vec![6.0, 3.0]));
+        assert_eq!(options.stroke_line_dash_offset, Some(1.5));
+        assert_eq!(options.dash_offset, Some(6.0));
+        assert_eq!(options.dash_gap, Some(3.0));
+    }
+
+    #[test]
+    fn line_detailed_returns_drawn_endpoints() {
+        let (drawable, start, end) =
+            Generator::default().line_detailed(10.0f32, 10.0, 90.0, 40.0, &None);
+        let ops = &drawable.sets[0].ops;
+        let last = &ops[ops.len() - 1].data;
+        assert_eq!(start, point2(ops[0].data[0], ops[0].data[1]));
+        assert_eq!(end, point2(last[4], last[5]));
+        assert_ne!(start, point2(10.0, 10.0));
+        assert!((end - point2(90.0, 40.0)).length() < 5.0);
+    }
+
+    #[test]
+    fn fill_region_hachures_overlaps_once() {
+        let square = |x: f32, y: f32| -> Vec<Point2D<f32>> {
+            vec![
+                point2(x, y),
+                point2(x + 40.0, y),
+                point2(x + 40.0, y + 40.0),
+                point2(x, y + 40.0),
+            ]
+        };
+        let options = OptionsBuilder::default()
+            .seed(3_u64)
+            .roughness(0.0)
+            .hachure_angle(-90.0)
+            .hachure_gap(2.0)
+            .disable_multi_stroke_fill(true)
+            .build()
+            .unwrap();
+        let drawable = Generator::default()
+            .fill_region(&[square(0.0, 0.0), square(20.0, 20.0)], &Some(options));
+        let lines_in = |from: f32, to: f32| {
+            drawable.sets[0]
+                .ops
+                .iter()
+                .filter(|op| op.op == OpType::Move && op.data[1] > from && op.data[1] < to)
+                .count()
+        };
+        let single = lines_in(2.0, 18.0);
+        assert!(single > 0);
+        assert_eq!(lines_in(22.0, 38.0), single);
+    }
+
+    #[test]
+    fn fill_style_all_lists_every_variant() {
+        // the match is exhaustive, so a new variant fails to compile here until it is listed
+        let index = |style: &FillStyle| match style {
+            FillStyle::Solid => 0,
+            FillStyle::Hachure => 1,
+            FillStyle::ZigZag => 2,
+            FillStyle::CrossHatch => 3,
+            FillStyle::Dots => 4,
+            FillStyle::Dashed => 5,
+            FillStyle::ZigZagLine => 6,
+            FillStyle::Concentric => 7,
+        };
+        let indices: Vec<usize> = FillStyle::ALL.iter().map(index).collect();
+        assert_eq!(indices, (0..8).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn clean_rectangle_hits_exact_corners() {
+        let options = OptionsBuilder::default()
+            .fill(Srgba::new(1.0, 0.0, 0.0, 1.0))
+            .build()
+            .unwrap();
+        let drawable =
+            Generator::default().clean_rectangle(10.0f32, 20.0, 30.0, 40.0, &Some(options));
+        assert_eq!(drawable.sets.len(), 1);
+        let corners: Vec<Point2D<f32>> = vec![
+            point2(10.0, 20.0),
+            point2(40.0, 20.0),
+            point2(40.0, 60.0),
+            point2(10.0, 60.0),
+        ];
+        let ends: Vec<Point2D<f32>> = drawable.sets[0]
+            .ops
+            .iter()
+            .map(|op| point2(op.data[op.data.len() - 2], op.data[op.data.len() - 1]))
+            .collect();
+        assert!(ends.iter().all(|end| corners.contains(end)));
+        assert!(corners.iter().all(|corner| ends.contains(corner)));
+    }
+
+    #[test]
+    fn drawable_is_empty_and_op_count() {
+        let generator = Generator::default();
+        let empty = generator.d::<&str, f32>("empty", &[], &None);
+        assert!(empty.is_empty());
+        assert_eq!(empty.op_count(), 0);
+
+        let rectangle = generator.rectangle(0.0f32, 0.0, 10.0, 10.0, &None);
+        assert!(!rectangle.is_empty());
+        let ops: usize = rectangle.sets.iter().map(|set| set.ops.len()).sum();
+        assert_eq!(rectangle.op_count(), ops);
+        assert!(rectangle.op_count() > 0);
+    }
+
+    #[test]
+    fn rectangle_fill_uses_stroke_corners() {
+        let options = OptionsBuilder::default()
+            .seed(11_u64)
+            .fill(Srgba::new(1.0, 0.0, 0.0, 1.0))
+            .build()
+            .unwrap();
+        let (drawable, polygons) =
+            Generator::default().rectangle_detailed(10.0f32, 10.0, 60.0, 40.0, &Some(options));
+        let outline = drawable
+            .sets
+            .iter()
+            .find(|set| set.op_set_type == OpSetType::Path)
+            .unwrap();
+        let first_pass: Vec<Point2D<f32>> = outline
+            .ops
+            .iter()
+            .filter(|op| op.op == OpType::Move)
+            .step_by(2)
+            .map(|op| point2(op.data[0], op.data[1]))
+            .collect();
+
+        assert_eq!(polygons, vec![first_pass]);
+        assert_ne!(polygons[0][0], point2(10.0, 10.0));
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn triangle_exports_one_json_ring() {
+        let options = OptionsBuilder::default()
+            .deterministic_preview(true)
+            .disable_multi_stroke(true)
+            .build()
+            .unwrap();
+        let triangle = [point2(0.0f32, 0.0), point2(40.0, 0.0), point2(20.0, 30.0)];
+        let json = Generator::default()
+            .polygon(&triangle, &Some(options))
+            .to_json_polylines(0.5);
+        let rings: Vec<Vec<[f64; 2]>> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(rings.len(), 1);
+        assert_eq!(rings[0].first(), rings[0].last());
+        assert!(rings[0].contains(&[40.0, 0.0]));
+        assert!(rings[0].contains(&[20.0, 30.0]));
+    }
+
+    #[test]
+    fn check_and_cross_marks_have_two_strokes() {
+        let generator = Generator::default();
+        let check = generator.check_mark(0.0f32, 0.0, 40.0, &None);
+        assert_eq!(check.shape, "check_mark");
+        assert_eq!(check.sets.len(), 2);
+
+        let cross = generator.cross_mark(0.0f32, 0.0, 40.0, &None);
+        assert_eq!(cross.sets.len(), 2);
+        for set in cross.sets.iter() {
+            let start = &set.ops[0].data;
+            let end = &set.ops[1].data;
+            let middle: Point2D<f32> = point2((start[0] + end[4]) / 2.0, (start[1] + end[5]) / 2.0);
+            assert!((middle - point2(20.0, 20.0)).length() < 3.0);
+        }
+    }
+
+    #[test]
+    fn scale_to_fit_preserves_aspect() {
+        let drawable = Generator::default().rectangle(10.0f32, 10.0, 100.0, 50.0, &None);
+        let before = drawable.bounds().unwrap().size();
+        let fitted = drawable.scale_to_fit(200.0, 200.0, true);
+        let after = fitted.bounds().unwrap();
+
+        assert!(after.min.x.abs() < 1e-3 && after.min.y.abs() < 1e-3);
+        assert!((after.width() - 200.0).abs() < 1e-3);
+        assert!(after.height() <= 200.0);
+        let ratio = |w: f32, h: f32| w / h;
+        assert!(
+            (ratio(after.width(), after.height()) - ratio(before.width, before.height)).abs()
+                < 1e-3
+        );
+
+        let stretched = drawable.scale_to_fit(200.0, 200.0, false).bounds().unwrap();
+        assert!((stretched.height() - 200.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn freehand_smooths_noisy_samples() {
+        let points: Vec<Point2D<f32>> = (0..100)
+            .map(|i| {
+                let x = i as f32 * 2.0;
+                let noise = if i % 2 == 0 { 0.3 } else { -0.3 };
+                point2(x, 20.0 * f32::sin(x / 40.0) + noise)
+            })
+            .collect();
+        let drawable = Generator::default().freehand(&points, &None);
+
+        assert_eq!(drawable.shape, "freehand");
+        assert_eq!(drawable.sets.len(), 1);
+        let ops = &drawable.sets[0].ops;
+        let curves = ops.iter().filter(|op| op.op == OpType::BCurveTo).count();
+        assert!(curves > 0);
+        assert!(ops.iter().all(|op| op.op != OpType::LineTo));
+        // two passes per segment
+        assert!(curves / 2 < points.len() / 4);
+
+        let generator = Generator::default();
+        assert!(generator.freehand::<f32>(&[], &None).is_empty());
+        assert!(generator.freehand(&[point2(1.0f32, 2.0)], &None).is_empty());
+    }
+
+    #[test]
+    fn clean_fill_keeps_fill_exact_under_rough_outline() {
+        let options = OptionsBuilder::default()
+            .fill(Srgba::new(1.0, 0.0, 0.0, 1.0))
+            .fill_style(FillStyle::Solid)
+            .roughness(3.0)
+            .clean_fill(true)
+            .build()
+            .unwrap();
+        let drawable = Generator::default().rectangle(10.0f32, 20.0, 100.0, 50.0, &Some(options));
+
+        let fill: Vec<Vec<f32>> = drawable.sets[0]
+            .ops
+            .iter()
+            .map(|op| op.data.clone())
+            .collect();
+        assert_eq!(drawable.sets[0].op_set_type, OpSetType::FillPath);
+        assert_eq!(
+            fill,
+            vec![
+                vec![10.0, 20.0],
+                vec![110.0, 20.0],
+                vec![110.0, 70.0],
+                vec![10.0, 70.0]
+            ]
+        );
+        let outline = &drawable.sets[1];
+        assert_eq!(outline.op_set_type, OpSetType::Path);
+        assert!(outline
+            .ops
+            .iter()
+            .filter(|op| op.op == OpType::Move)
+            .any(|op| !fill.contains(&op.data)));
+
+        let options = OptionsBuilder::default()
+            .fill(Srgba::new(1.0, 0.0, 0.0, 1.0))
+            .roughness(3.0)
+            .clean_fill(true)
+            .build()
+            .unwrap();
+        let (_, polygons) =
+            Generator::default().ellipse_detailed(50.0f32, 50.0, 80.0, 40.0, &Some(options));
+        for p in polygons[0].iter() {
+            let distance = ((p.x - 50.0) / 40.0).powi(2) + ((p.y - 50.0) / 20.0).powi(2);
+            assert!((distance - 1.0).abs() < 1e-3);
+        }
+    }
+
+    fn subpaths_op_set() -> crate::core::OpSet<f32> {
+        use crate::core::Op;
+        let op = |op: OpType, data: &[f32]| Op { op, data: data.to_vec() };
+        crate::core::OpSet {
+            op_set_type: OpSetType::Path,
+            ops: vec![
+                op(OpType::Move, &[0.0, 0.0]),
+                op(OpType::LineTo, &[10.0, 0.0]),
+                op(OpType::Move, &[20.0, 0.0]),
+                op(OpType::BCurveTo, &[21.0, 1.0, 22.0, 2.0, 23.0, 3.0]),
+            ],
+            size: None,
+            path: None,
+        }
+    }
+
+    #[test]
+    fn ops_to_path_starts_subpaths_with_move() {
+        assert_eq!(
+            Generator::ops_to_path(subpaths_op_set(), None),
+            "M0 0 L10 0 M20 0 C21 1, 22 2, 23 3 "
+        );
+    }
+
+    #[test]
+    fn ops_to_path_output_parses() {
+        use svgtypes::{PathParser, PathSegment};
+
+        let path = Generator::ops_to_path(subpaths_op_set(), None);
+        // the svg path grammar allows no comma before a command, strict parsers stop there
+        let tokens: Vec<&str> = path.split_whitespace().collect();
+        for pair in tokens.windows(2) {
+            if pair[1].starts_with(char::is_alphabetic) {
+                assert!(!pair[0].ends_with(','), "{}", path);
+            }
+        }
+        let segments: Vec<PathSegment> = PathParser::from(path.as_str())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            segments,
+            vec![
+                PathSegment::MoveTo { abs: true, x: 0.0, y: 0.0 },
+                PathSegment::LineTo { abs: true, x: 10.0, y: 0.0 },
+                PathSegment::MoveTo { abs: true, x: 20.0, y: 0.0 },
+                PathSegment::CurveTo {
+                    abs: true,
+                    x1: 21.0,
+                    y1: 1.0,
+                    x2: 22.0,
+                    y2: 2.0,
+                    x: 23.0,
+                    y: 3.0
+                },
+            ]
+        );
+    }
+}
@@ -102,6 +102,273 @@ pub fn convert_bezier_quadratic_to_cubic<F: Float + FromPrimitive + Trig>(
     }
 }
 
+/// Insets a simple polygon by `distance`, moving every edge inwards along its normal and
+/// joining neighbouring edges with miters. Returns `None` once the polygon collapses, i.e.
+/// when an edge flips direction or the polygon stops shrinking.
+pub fn offset_polygon<F: Float + FromPrimitive + Trig>(
+    polygon: &[Point2D<F>],
+    distance: F,
+) -> Option<Vec<Point2D<F>>> {
+    let mut points: Vec<Point2D<F>> = polygon.to_vec();
+    points.dedup();
+    if points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+    let len = points.len();
+    if len < 3 {
+        return None;
+    }
+    let area = polygon_area(&points);
+    if area == F::zero() {
+        return None;
+    }
+    let side = area.signum();
+    let normal = |a: Point2D<F>, b: Point2D<F>| {
+        let d = (b - a).normalize();
+        Vector2D::new(-d.y, d.x) * side * distance
+    };
+
+    let mut result = Vec::with_capacity(len);
+    for i in 0..len {
+        let prev = points[(i + len - 1) % len];
+        let curr = points[i];
+        let next = points[(i + 1) % len];
+        let a1 = prev + normal(prev, curr);
+        let d1 = curr - prev;
+        let a2 = curr + normal(curr, next);
+        let d2 = next - curr;
+        let denominator = d1.cross(d2);
+        if denominator.abs() < _c(1e-9) {
+            result.push(a2);
+        } else {
+            let t = (a2 - a1).cross(d2) / denominator;
+            result.push(a1 + d1 * t);
+        }
+    }
+
+    let new_area = polygon_area(&result);
+    let flipped = (0..len).any(|i| {
+        let old_edge = points[(i + 1) % len] - points[i];
+        let new_edge = result[(i + 1) % len] - result[i];
+        old_edge.dot(new_edge) <= F::zero()
+    });
+    if flipped || new_area.signum() != side || new_area.abs() >= area.abs() {
+        None
+    } else {
+        Some(result)
+    }
+}
+
+/// Even-odd point in polygon test.
+pub fn point_in_polygon<F: Float + Trig>(p: Point2D<F>, polygon: &[Point2D<F>]) -> bool {
+    let len = polygon.len();
+    let mut inside = false;
+    for i in 0..len {
+        let a = polygon[i];
+        let b = polygon[(i + 1) % len];
+        if (a.y > p.y) != (b.y > p.y) && p.x < a.x + (p.y - a.y) / (b.y - a.y) * (b.x - a.x) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+/// Returns the pieces of the segment from `start` to `end` that lie inside `polygon`.
+pub fn clip_segment_to_polygon<F: Float + FromPrimitive + Trig>(
+    start: Point2D<F>,
+    end: Point2D<F>,
+    polygon: &[Point2D<F>],
+) -> Vec<(Point2D<F>, Point2D<F>)> {
+    let direction = end - start;
+    let len = polygon.len();
+    let mut params = vec![F::zero(), F::one()];
+    for i in 0..len {
+        let a = polygon[i];
+        let edge = polygon[(i + 1) % len] - a;
+        let denominator = direction.cross(edge);
+        if denominator == F::zero() {
+            continue;
+        }
+        let t = (a - start).cross(edge) / denominator;
+        let u = (a - start).cross(direction) / denominator;
+        if t.is_finite() && t > F::zero() && t < F::one() && u >= F::zero() && u <= F::one() {
+            params.push(t);
+        }
+    }
+    params.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    params
+        .windows(2)
+        .filter(|w| w[1] > w[0])
+        .filter(|w| point_in_polygon(start.lerp(end, (w[0] + w[1]) / _c(2.0)), polygon))
+        .map(|w| (start.lerp(end, w[0]), start.lerp(end, w[1])))
+        .collect()
+}
+
+/// Clips `subject` against `clip` with the Sutherland-Hodgman algorithm. The result is
+/// only exact when `clip` is convex.
+pub fn clip_polygon<F: Float + FromPrimitive + Trig>(
+    subject: &[Point2D<F>],
+    clip: &[Point2D<F>],
+) -> Vec<Point2D<F>> {
+    let side = polygon_area(clip).signum();
+    let len = clip.len();
+    let mut output = subject.to_vec();
+    for i in 0..len {
+        if output.is_empty() {
+            break;
+        }
+        let a = clip[i];
+        let b = clip[(i + 1) % len];
+        let inside = |p: Point2D<F>| (b - a).cross(p - a) * side >= F::zero();
+        let intersection = |p: Point2D<F>, q: Point2D<F>| {
+            let d = q - p;
+            let t = (a - p).cross(b - a) / d.cross(b - a);
+            p + d * t
+        };
+        let input = std::mem::take(&mut output);
+        for j in 0..input.len() {
+            let current = input[j];
+            let previous = input[(j + input.len() - 1) % input.len()];
+            if inside(current) {
+                if !inside(previous) {
+                    output.push(intersection(previous, current));
+                }
+                output.push(current);
+            } else if inside(previous) {
+                output.push(intersection(previous, current));
+            }
+        }
+    }
+    output
+}
+
+/// Signed area of a polygon using the shoelace formula. The sign tells the winding
+/// direction of the vertices.
+pub fn polygon_area<F: Float + Trig>(points: &[Point2D<F>]) -> F {
+    let len = points.len();
+    (0..len).fold(F::zero(), |acc, i| {
+        let p = points[i];
+        let q = points[(i + 1) % len];
+        acc + p.x * q.y - q.x * p.y
+    }) / (F::one() + F::one())
+}
+
+/// Centroid of a polygon's area. Returns `None` for degenerate polygons with zero area.
+pub fn polygon_centroid<F: Float + FromPrimitive + Trig>(
+    points: &[Point2D<F>],
+) -> Option<Point2D<F>> {
+    let area = polygon_area(points);
+    if area == F::zero() {
+        return None;
+    }
+    let len = points.len();
+    let (cx, cy) = (0..len).fold((F::zero(), F::zero()), |(cx, cy), i| {
+        let p = points[i];
+        let q = points[(i + 1) % len];
+        let cross = p.x * q.y - q.x * p.y;
+        (cx + (p.x + q.x) * cross, cy + (p.y + q.y) * cross)
+    });
+    let factor = _c::<F>(6.0) * area;
+    Some(Point2D::new(cx / factor, cy / factor))
+}
+
+/// Direction of the principal axis of `points`, in degrees, from the covariance of the points.
+/// Returns `None` when there are no points.
+pub fn principal_axis_angle<F: Float + FromPrimitive + Trig>(points: &[Point2D<F>]) -> Option<F> {
+    if points.is_empty() {
+        return None;
+    }
+    let count = F::from_usize(points.len()).unwrap();
+    let mean = points
+        .iter()
+        .fold(Vector2D::zero(), |acc, p| acc + p.to_vector())
+        / count;
+    let (xx, yy, xy) = points
+        .iter()
+        .fold((F::zero(), F::zero(), F::zero()), |(xx, yy, xy), p| {
+            let d = p.to_vector() - mean;
+            (xx + d.x * d.x, yy + d.y * d.y, xy + d.x * d.y)
+        });
+    let angle = Float::atan2(xy + xy, xx - yy) / _c(2.0);
+    Some(angle.to_degrees())
+}
+
+/// Splits the polyline through `points` into `dash` long pieces separated by `gap`. Dashes
+/// continue around corners. Returns the whole polyline for non positive `dash` or `gap`.
+pub fn dash_polyline<F: Float + FromPrimitive + Trig>(
+    points: &[Point2D<F>],
+    dash: F,
+    gap: F,
+) -> Vec<Vec<Point2D<F>>> {
+    if points.len() < 2 || dash <= F::zero() || gap <= F::zero() {
+        return vec![points.to_vec()];
+    }
+    let mut dashes = vec![];
+    let mut current = vec![points[0]];
+    let mut on = true;
+    let mut remaining = dash;
+    for segment in points.windows(2) {
+        let (from, to) = (segment[0], segment[1]);
+        let length = (to - from).length();
+        let mut position = F::zero();
+        while length - position >= remaining {
+            position = position + remaining;
+            let p = from.lerp(to, position / length);
+            if on {
+                current.push(p);
+                dashes.push(std::mem::take(&mut current));
+            } else {
+                current = vec![p];
+            }
+            on = !on;
+            remaining = if on { dash } else { gap };
+        }
+        remaining = remaining - (length - position);
+        if on {
+            current.push(to);
+        }
+    }
+    if on && current.len() > 1 {
+        dashes.push(current);
+    }
+    dashes
+}
+
+/// Convex hull of `points` using Andrew's monotone chain. The hull starts at the leftmost
+/// point and runs counter clockwise, skipping collinear points. Inputs with fewer than three
+/// distinct points are returned sorted and deduplicated.
+pub fn convex_hull<F: Float + Trig>(points: &[Point2D<F>]) -> Vec<Point2D<F>> {
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| {
+        a.x.partial_cmp(&b.x)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.y.partial_cmp(&b.y).unwrap_or(std::cmp::Ordering::Equal))
+    });
+    sorted.dedup();
+    if sorted.len() < 3 {
+        return sorted;
+    }
+    let cross = |o: Point2D<F>, a: Point2D<F>, b: Point2D<F>| (a - o).cross(b - o);
+    let mut hull: Vec<Point2D<F>> = Vec::with_capacity(sorted.len() * 2);
+    for &p in sorted.iter() {
+        while hull.len() >= 2 && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= F::zero() {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+    let lower_len = hull.len() + 1;
+    for &p in sorted.iter().rev().skip(1) {
+        while hull.len() >= lower_len
+            && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= F::zero()
+        {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+    hull.pop();
+    hull
+}
+
 #[cfg(test)]
 mod tests {
     use euclid::default::Point2D;
@@ -110,4 +377,121 @@ mod tests {
         let l = super::Line::from(&[Point2D::new(1.0, 1.0), Point2D::new(2.0, 2.0)]);
         assert_eq!(l.length(), f32::sqrt(2.0));
     }
+
+    #[test]
+    fn offset_polygon_insets_square() {
+        let square = vec![
+            Point2D::new(0.0, 0.0),
+            Point2D::new(10.0, 0.0),
+            Point2D::new(10.0, 10.0),
+            Point2D::new(0.0, 10.0),
+        ];
+        let inset = super::offset_polygon(&square, 2.0).unwrap();
+        assert_eq!(
+            inset,
+            vec![
+                Point2D::new(2.0, 2.0),
+                Point2D::new(8.0, 2.0),
+                Point2D::new(8.0, 8.0),
+                Point2D::new(2.0, 8.0),
+            ]
+        );
+        assert_eq!(super::offset_polygon(&square, 6.0), None);
+    }
+
+    #[test]
+    fn unit_square_area_and_centroid() {
+        let square = vec![
+            Point2D::new(0.0, 0.0),
+            Point2D::new(1.0, 0.0),
+            Point2D::new(1.0, 1.0),
+            Point2D::new(0.0, 1.0),
+        ];
+        assert_eq!(super::polygon_area(&square), 1.0);
+        assert_eq!(
+            super::polygon_centroid(&square),
+            Some(Point2D::new(0.5, 0.5))
+        );
+    }
+
+    #[test]
+    fn triangle_area_and_centroid() {
+        let triangle = vec![
+            Point2D::new(0.0, 0.0),
+            Point2D::new(0.0, 3.0),
+            Point2D::new(6.0, 0.0),
+        ];
+        assert_eq!(super::polygon_area(&triangle), -9.0);
+        assert_eq!(
+            super::polygon_centroid(&triangle),
+            Some(Point2D::new(2.0, 1.0))
+        );
+    }
+
+    #[test]
+    fn convex_hull_drops_interior_point() {
+        let points = vec![
+            Point2D::new(5.0, 5.0),
+            Point2D::new(0.0, 0.0),
+            Point2D::new(10.0, 10.0),
+            Point2D::new(10.0, 0.0),
+            Point2D::new(0.0, 10.0),
+        ];
+        assert_eq!(
+            super::convex_hull(&points),
+            vec![
+                Point2D::new(0.0, 0.0),
+                Point2D::new(10.0, 0.0),
+                Point2D::new(10.0, 10.0),
+                Point2D::new(0.0, 10.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn convex_hull_of_collinear_points_is_its_end_points() {
+        let points = vec![
+            Point2D::new(2.0, 2.0),
+            Point2D::new(0.0, 0.0),
+            Point2D::new(3.0, 3.0),
+            Point2D::new(1.0, 1.0),
+        ];
+        assert_eq!(
+            super::convex_hull(&points),
+            vec![Point2D::new(0.0, 0.0), Point2D::new(3.0, 3.0)]
+        );
+        let square_edge = vec![
+            Point2D::new(0.0, 0.0),
+            Point2D::new(5.0, 0.0),
+            Point2D::new(10.0, 0.0),
+            Point2D::new(10.0, 10.0),
+        ];
+        assert_eq!(super::convex_hull(&square_edge).len(), 3);
+    }
+
+    #[test]
+    fn clip_segment_ignores_nan_input() {
+        let square = vec![
+            Point2D::new(0.0, 0.0),
+            Point2D::new(10.0, 0.0),
+            Point2D::new(10.0, 10.0),
+            Point2D::new(0.0, 10.0),
+        ];
+        let pieces = super::clip_segment_to_polygon(
+            Point2D::new(f32::NAN, 5.0),
+            Point2D::new(5.0, 5.0),
+            &square,
+        );
+        assert!(pieces.is_empty());
+
+        let pieces = super::clip_segment_to_polygon(
+            Point2D::new(-5.0, 5.0),
+            Point2D::new(5.0, 5.0),
+            &square,
+        );
+        assert_eq!(
+            pieces,
+            vec![(Point2D::new(0.0, 5.0), Point2D::new(5.0, 5.0))]
+        );
+    }
 }
@@ -158,12 +158,17 @@
 #[macro_use]
 extern crate derive_builder;
 
+pub mod backend;
 pub mod core;
+pub mod error;
 pub mod filler;
 pub mod generator;
 pub mod geometry;
 pub mod points_on_path;
 pub mod renderer;
+pub mod stamp;
+#[cfg(feature = "svg-import")]
+mod svg_elements;
 
 pub use euclid::Point2D;
 pub use palette::Srgba;
@@ -5,8 +5,8 @@ use euclid::default::Point2D;
 use euclid::Trig;
 use num_traits::{Float, FromPrimitive};
 use points_on_curve::{points_on_bezier_curves, simplify};
-use svg_path_ops::{absolutize, normalize};
-use svgtypes::{PathParser, PathSegment};
+use svg_path_ops::{absolutize, normalize, to_canonical};
+use svgtypes::PathSegment;
 
 use crate::core::{_c, _cc};
 
@@ -18,11 +18,9 @@ pub fn points_on_path<F>(
 where
     F: FromPrimitive + Trig + Float + MulAssign + Display,
 {
-    let path_parser = PathParser::from(path.as_ref());
-    let path_segments: Vec<PathSegment> = path_parser.flatten().collect();
-    let normalized_segments = normalize(absolutize(path_segments.iter()));
+    let normalized_segments = to_canonical(&path);
 
-    generate_points(tolerance, distance, normalized_segments)
+    generate_points(tolerance, distance, normalized_segments.into_iter())
 }
 
 pub fn points_on_segments<F>(
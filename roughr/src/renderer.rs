@@ -1,15 +1,19 @@
 use std::borrow::BorrowMut;
+use std::fmt::Display;
+use std::ops::MulAssign;
 
-use euclid::default::Point2D;
+use euclid::default::{Box2D, Point2D, Vector2D};
 use euclid::{point2, Trig};
 use num_traits::{Float, FloatConst, FromPrimitive};
-use svg_path_ops::{absolutize, normalize};
-use svgtypes::{PathParser, PathSegment};
+use points_on_curve::simplify;
+use svg_path_ops::{absolutize, normalize, to_canonical};
+use svgtypes::PathSegment;
 
 use super::core::{Options, _c};
 use crate::core::{FillStyle, Op, OpSet, OpSetType, OpType, _cc};
 use crate::filler::get_filler;
 use crate::filler::FillerType::{
+    ConcentricFiller,
     DashedFiller,
     DotFiller,
     HatchFiller,
@@ -17,7 +21,8 @@ use crate::filler::FillerType::{
     ZigZagFiller,
     ZigZagLineFiller,
 };
-use crate::geometry::{convert_bezier_quadratic_to_cubic, BezierQuadratic};
+use crate::geometry::{convert_bezier_quadratic_to_cubic, principal_axis_angle, BezierQuadratic};
+use crate::points_on_path::points_on_path;
 
 #[derive(PartialEq, Eq, Debug)]
 pub struct EllipseParams<F: Float> {
@@ -111,6 +116,23 @@ pub fn line<F: Float + Trig + FromPrimitive>(
     }
 }
 
+/// Draws a single point, jittered by up to half of `max_randomness_offset`, as a zero
+/// length stroke. Used for shapes that have no size.
+pub fn dot<F: Float + Trig + FromPrimitive>(x: F, y: F, o: &mut Options) -> OpSet<F> {
+    let offset = _c::<F>(o.max_randomness_offset.unwrap_or(2.0) / 2.0);
+    let x = x + _offset_opt(offset, o, None);
+    let y = y + _offset_opt(offset, o, None);
+    OpSet {
+        op_set_type: OpSetType::Path,
+        ops: vec![
+            Op { op: OpType::Move, data: vec![x, y] },
+            Op { op: OpType::LineTo, data: vec![x, y] },
+        ],
+        size: None,
+        path: None,
+    }
+}
+
 /// Constructs a linear path with given points by connecting consecutive points
 /// with rough line primitives. This function is also used by other high level
 /// constructs such as rectangle and polygon. For two element point list
@@ -204,28 +226,20 @@ pub fn linear_path<F: Float + Trig + FromPrimitive>(
     let len = points.len();
     if len > 2 {
         let mut ops: Vec<Op<F>> = Vec::new();
-        let mut i = 0;
-        while i < (len - 1) {
-            ops.append(&mut _double_line(
-                points[i].x,
-                points[i].y,
-                points[i + 1].x,
-                points[i + 1].y,
-                o,
-                false,
-            ));
-            i += 1;
-        }
+        let mut lengths: Vec<F> = points.windows(2).map(|w| (w[1] - w[0]).length()).collect();
         if close {
-            ops.append(&mut _double_line(
-                points[len - 1].x,
-                points[len - 1].y,
-                points[0].x,
-                points[0].y,
-                o,
-                false,
-            ));
+            lengths.push((points[0] - points[len - 1]).length());
+        }
+        let total = lengths.iter().fold(F::zero(), |acc, l| acc + *l);
+        let roughness = o.roughness;
+        let mut travelled = F::zero();
+        for (i, length) in lengths.into_iter().enumerate() {
+            o.roughness = _ramped_roughness(o, roughness, travelled + length / _c(2.0), total);
+            travelled = travelled + length;
+            let (from, to) = (points[i], points[(i + 1) % len]);
+            ops.append(&mut _double_line(from.x, from.y, to.x, to.y, o, false));
         }
+        o.roughness = roughness;
         OpSet {
             op_set_type: OpSetType::Path,
             ops: ops,
@@ -300,7 +314,87 @@ pub fn bezier_cubic<F: Float + Trig + FromPrimitive>(
     }
 }
 
+/// Same as [`linear_path`] but every point carries a weight that scales the roughness
+/// around it. A weight of 0 keeps the vertex exactly in place, 1 roughens it as usual.
+pub fn linear_path_weighted<F: Float + Trig + FromPrimitive>(
+    points: &[(Point2D<F>, f32)],
+    close: bool,
+    o: &mut Options,
+) -> OpSet<F> {
+    let len = points.len();
+    let mut ops: Vec<Op<F>> = Vec::new();
+    let segment_count = match len {
+        0 | 1 => 0,
+        2 => 1,
+        _ if close => len,
+        _ => len - 1,
+    };
+    let roughness = o.roughness;
+    for i in 0..segment_count {
+        let (from, from_weight) = points[i];
+        let (to, to_weight) = points[(i + 1) % len];
+        let weight = from_weight.max(to_weight).max(0.0);
+        o.roughness = roughness.map(|r| r * weight);
+        let mut segment = _double_line(from.x, from.y, to.x, to.y, o, false);
+        let (from_scale, to_scale) = if weight > 0.0 {
+            (
+                _c::<F>(from_weight.max(0.0) / weight),
+                _c::<F>(to_weight.max(0.0) / weight),
+            )
+        } else {
+            (F::zero(), F::zero())
+        };
+        // only the start of each pass and the end of its last curve touch the vertices
+        let pass_ends: Vec<bool> = (0..segment.len())
+            .map(|j| {
+                segment
+                    .get(j + 1)
+                    .is_none_or(|next| next.op == OpType::Move)
+            })
+            .collect();
+        for (op, pass_end) in segment.iter_mut().zip(pass_ends) {
+            let (anchor, scale, at) = match op.op {
+                OpType::Move => (from, from_scale, 0),
+                OpType::BCurveTo if pass_end => (to, to_scale, 4),
+                _ => continue,
+            };
+            op.data[at] = anchor.x + (op.data[at] - anchor.x) * scale;
+            op.data[at + 1] = anchor.y + (op.data[at + 1] - anchor.y) * scale;
+        }
+        ops.append(&mut segment);
+    }
+    o.roughness = roughness;
+    OpSet {
+        op_set_type: OpSetType::Path,
+        ops,
+        path: None,
+        size: None,
+    }
+}
+
 pub fn curve<F: Float + Trig + FromPrimitive>(points: &[Point2D<F>], o: &mut Options) -> OpSet<F> {
+    if o.parallel_offset_strokes.unwrap_or(false) {
+        let ps = _curve_points_with_offset(
+            points,
+            _c::<F>(1.0) * _c(1.0 + o.roughness.unwrap_or(0.0) * 0.2),
+            o,
+        );
+        let mut o1 = _curve(&ps, None, o);
+        if !o.disable_multi_stroke.unwrap_or(false) {
+            let distance = _c::<F>(
+                o.roughness.unwrap_or(1.0) * o.max_randomness_offset.unwrap_or(2.0) / 2.0
+                    * o.multi_stroke_offset.unwrap_or(1.0),
+            );
+            let mut o2 = _curve_parallel_offset(&ps, distance, o);
+            o1.append(&mut o2);
+        }
+        return OpSet {
+            op_set_type: OpSetType::Path,
+            ops: o1,
+            path: None,
+            size: None,
+        };
+    }
     let mut o1 = _curve_with_offset(
         points,
         _c::<F>(1.0) * _c(1.0 + o.roughness.unwrap_or(0.0) * 0.2),
@@ -309,9 +403,11 @@ pub fn curve<F: Float + Trig + FromPrimitive>(points: &[Point2D<F>], o: &mut Opt
     if !o.disable_multi_stroke.unwrap_or(false) {
         let mut o2 = _curve_with_offset(
             points,
-            _c::<F>(1.5) * _c(1.0 + o.roughness.unwrap_or(0.0) * 0.22),
+            _c::<F>(1.5 * o.multi_stroke_offset.unwrap_or(1.0))
+                * _c(1.0 + o.roughness.unwrap_or(0.0) * 0.22),
             &mut clone_options_alter_seed(o),
         );
+        _correlate_second_pass(&o1, &mut o2, o);
         o1.append(&mut o2);
     }
     OpSet {
@@ -338,6 +434,13 @@ pub fn generate_ellipse_params<F: Float + Trig + FromPrimitive>(
     height: F,
     o: &mut Options,
 ) -> EllipseParams<F> {
+    let increment = ellipse_increment(width, height, o);
+    randomize_ellipse_params(increment, width, height, o)
+}
+
+/// Angular step between the sample points of an ellipse. Depends only on the size and
+/// `curve_step_count`, so it can be shared by ellipses of the same size.
+pub fn ellipse_increment<F: Float + Trig + FromPrimitive>(width: F, height: F, o: &Options) -> F {
     let psq: F = Float::sqrt(
         _c::<F>(f32::PI())
             * _c(2.0)
@@ -349,12 +452,23 @@ pub fn generate_ellipse_params<F: Float + Trig + FromPrimitive>(
         _c(o.curve_step_count.unwrap_or(1.0)),
         _c::<F>(o.curve_step_count.unwrap_or(1.0) / Float::sqrt(200.0)) * psq,
     ));
-    let increment: F = (_c::<F>(f32::PI()) * _c(2.0)) / step_count;
+    (_c::<F>(f32::PI()) * _c(2.0)) / step_count
+}
+
+/// Completes [`EllipseParams`] for a precomputed `increment` by randomizing the radii.
+pub fn randomize_ellipse_params<F: Float + Trig + FromPrimitive>(
+    increment: F,
+    width: F,
+    height: F,
+    o: &mut Options,
+) -> EllipseParams<F> {
     let mut rx = Float::abs(width / _c(2.0));
     let mut ry = Float::abs(height / _c(2.0));
-    let curve_fit_randomness: F = _c::<F>(1.0) - _c(o.curve_fitting.unwrap_or(0.0));
-    rx = rx + _offset_opt(rx * curve_fit_randomness, o, None);
-    ry = ry + _offset_opt(ry * curve_fit_randomness, o, None);
+    if !o.perfect_ellipse.unwrap_or(false) {
+        let curve_fit_randomness: F = _c::<F>(1.0) - _c(o.curve_fitting.unwrap_or(0.0));
+        rx = rx + _offset_opt(rx * curve_fit_randomness, o, None);
+        ry = ry + _offset_opt(ry * curve_fit_randomness, o, None);
+    }
     EllipseParams { increment, rx, ry }
 }
 
@@ -384,19 +498,22 @@ pub fn ellipse_with_params<F: Float + Trig + FromPrimitive>(
     let cp1 = ellipse_points[1].clone();
     let mut o1 = _curve(&ap1, None, o);
     if (!o.disable_multi_stroke.unwrap_or(false)) && (o.roughness.unwrap_or(0.0) != 0.0) {
+        // the inner pass draws from its own random stream so it does not depend on the outer pass
+        let mut inner_options = clone_options_derive_seed(o);
         let inner_ellipse_points = _compute_ellipse_points(
             ellipse_params.increment,
             x,
             y,
             ellipse_params.rx,
             ellipse_params.ry,
-            _c::<F>(1.5),
+            _c::<F>(1.5 * o.multi_stroke_offset.unwrap_or(1.0)),
             _c::<F>(0.0),
-            o,
+            &mut inner_options,
         );
         let ap2 = inner_ellipse_points[0].clone();
         let _cp2 = inner_ellipse_points[1].clone();
-        let mut o2 = _curve(&ap2, None, o);
+        let mut o2 = _curve(&ap2, None, &mut inner_options);
+        _correlate_second_pass(&o1, &mut o2, o);
         o1.append(&mut o2);
     }
     EllipseResult {
@@ -410,6 +527,38 @@ pub fn ellipse_with_params<F: Float + Trig + FromPrimitive>(
     }
 }
 
+/// Single pass, open ellipse that starts at a random angle and sweeps past its start on a
+/// slightly wider radius, so the stroke ends beside where it began like a quick pen circle.
+pub fn highlight_ellipse<F: Float + Trig + FromPrimitive>(
+    cx: F,
+    cy: F,
+    rx: F,
+    ry: F,
+    o: &mut Options,
+) -> OpSet<F> {
+    let rx = Float::abs(rx);
+    let ry = Float::abs(ry);
+    let increment = ellipse_increment(rx * _c(2.0), ry * _c(2.0), o);
+    let start: F = _c::<F>(o.random() as f32) * _c(f32::PI() * 2.0);
+    let sweep: F = _c(f32::PI() * 2.0 * 1.15);
+    let mut points = vec![];
+    let mut angle = F::zero();
+    while angle <= sweep {
+        let grow = F::one() + _c::<F>(0.1) * angle / sweep;
+        points.push(Point2D::new(
+            cx + _offset_opt(rx * _c(0.01), o, None) + rx * grow * Float::cos(start + angle),
+            cy + _offset_opt(ry * _c(0.01), o, None) + ry * grow * Float::sin(start + angle),
+        ));
+        angle = angle + increment;
+    }
+    OpSet {
+        op_set_type: OpSetType::Path,
+        ops: _curve(&points, None, o),
+        size: None,
+        path: None,
+    }
+}
+
 pub fn arc<F: Float + Trig + FromPrimitive>(
     x: F,
     y: F,
@@ -441,7 +590,9 @@ pub fn arc<F: Float + Trig + FromPrimitive>(
     let arc_inc = Float::min(ellipse_inc / _c(2.0), (stp - strt) / _c(2.0));
     let mut ops = _arc(arc_inc, cx, cy, rx, ry, strt, stp, _c(1.0), o);
     if !o.disable_multi_stroke.unwrap_or(false) {
-        let mut o2 = _arc(arc_inc, cx, cy, rx, ry, strt, stp, _c(1.5), o);
+        let offset = _c(1.5 * o.multi_stroke_offset.unwrap_or(1.0));
+        let mut o2 = _arc(arc_inc, cx, cy, rx, ry, strt, stp, offset, o);
+        _correlate_second_pass(&ops, &mut o2, o);
         ops.append(&mut o2);
     }
     if closed {
@@ -478,6 +629,10 @@ pub fn arc<F: Float + Trig + FromPrimitive>(
     }
 }
 
+/// Constructs a solid fill for given polygons. Each polygon is emitted as a single
+/// ring of move/line operations without any triangulation, so rings may be concave
+/// and several rings may form holes. Adapters must fill the resulting
+/// [`OpSetType::FillPath`] with the even-odd fill rule.
 pub fn solid_fill_polygon<F: Float + Trig + FromPrimitive>(
     polygon_list: &Vec<Vec<Point2D<F>>>,
     options: &mut Options,
@@ -485,7 +640,11 @@ pub fn solid_fill_polygon<F: Float + Trig + FromPrimitive>(
     let mut ops = vec![];
     for polygon in polygon_list {
         if polygon.len() > 2 {
-            let rand_offset = _c(options.max_randomness_offset.unwrap_or(2.0));
+            let rand_offset = if options.clean_fill.unwrap_or(false) {
+                F::zero()
+            } else {
+                _c(options.max_randomness_offset.unwrap_or(2.0))
+            };
             polygon.iter().enumerate().for_each(|(ind, point)| {
                 if ind == 0 {
                     ops.push(Op {
@@ -545,12 +704,24 @@ fn clone_options_alter_seed(ops: &mut Options) -> Options {
     result
 }
 
+/// Copy of `ops` with a fresh random stream seeded by one draw from `ops`, so the copy is
+/// reproducible for a given seed but differs between consecutive shapes.
+fn clone_options_derive_seed(ops: &mut Options) -> Options {
+    let mut result: Options = ops.clone();
+    result.seed = Some((ops.next_random() * u64::MAX as f64) as u64);
+    result.randomizer = None;
+    result
+}
+
 fn _offset<F: Float + Trig + FromPrimitive>(
     min: F,
     max: F,
     ops: &mut Options,
     roughness_gain: Option<F>,
 ) -> F {
+    if ops.deterministic_preview.unwrap_or(false) {
+        return F::zero();
+    }
     let rg: F = roughness_gain.unwrap_or_else(|| _c(1.0));
     _c::<F>(ops.roughness.unwrap_or(1.0))
         * rg
@@ -601,6 +772,7 @@ fn _line<F: Float + Trig + FromPrimitive>(
         / _c(200.0);
     mid_disp_x = _offset_opt(mid_disp_x, o, Some(roughness_gain));
     mid_disp_y = _offset_opt(mid_disp_y, o, Some(roughness_gain));
+    let (mid_disp_x, mid_disp_y) = _clamp_bowing(mid_disp_x, mid_disp_y, length, o);
     let mut ops: Vec<Op<F>> = Vec::new();
 
     let preserve_vertices = o.preserve_vertices.unwrap_or(false);
@@ -707,6 +879,24 @@ fn _line<F: Float + Trig + FromPrimitive>(
     ops
 }
 
+/// Scales the bowing displacement down to at most `max_bowing_ratio * length`.
+fn _clamp_bowing<F: Float + Trig + FromPrimitive>(
+    mid_disp_x: F,
+    mid_disp_y: F,
+    length: F,
+    o: &Options,
+) -> (F, F) {
+    if let Some(ratio) = o.max_bowing_ratio {
+        let max = length * _c(ratio);
+        let disp = mid_disp_x.hypot(mid_disp_y);
+        if disp > max && disp > F::zero() {
+            let scale = max / disp;
+            return (mid_disp_x * scale, mid_disp_y * scale);
+        }
+    }
+    (mid_disp_x, mid_disp_y)
+}
+
 pub(crate) fn _double_line<F: Float + Trig + FromPrimitive>(
     x1: F,
     y1: F,
@@ -720,16 +910,186 @@ pub(crate) fn _double_line<F: Float + Trig + FromPrimitive>(
     } else {
         o.disable_multi_stroke.unwrap_or(false)
     };
-    let mut o1 = _line(x1, y1, x2, y2, o, true, false);
+    let pencil = !filling && o.pencil_texture.unwrap_or(false);
+    let pass = |o: &mut Options, overlay: bool| {
+        let ops = _segmented_line(x1, y1, x2, y2, o, overlay);
+        if pencil {
+            _pencil_texture(ops, o)
+        } else {
+            ops
+        }
+    };
+    let mut o1 = pass(o, false);
     if single_stroke {
         o1
     } else {
-        let mut o2 = _line(x1, y1, x2, y2, o, true, true);
+        let mut o2 = pass(o, true);
+        _correlate_second_pass(&o1, &mut o2, o);
         o1.append(&mut o2);
         o1
     }
 }
 
+/// Roughens a line in pieces of at most `max_segment_length`, continuing each piece from
+/// the end of the previous one.
+fn _segmented_line<F: Float + Trig + FromPrimitive>(
+    x1: F,
+    y1: F,
+    x2: F,
+    y2: F,
+    o: &mut Options,
+    overlay: bool,
+) -> Vec<Op<F>> {
+    let length = (x2 - x1).hypot(y2 - y1);
+    let pieces = match o.max_segment_length {
+        Some(max) if max > 0.0 => (length / _c(max)).ceil().to_usize().unwrap_or(1).max(1),
+        _ => 1,
+    };
+    let from = Point2D::new(x1, y1);
+    let to = Point2D::new(x2, y2);
+    let mut ops = vec![];
+    for i in 0..pieces {
+        let a = from.lerp(
+            to,
+            F::from_usize(i).unwrap() / F::from_usize(pieces).unwrap(),
+        );
+        let b = from.lerp(
+            to,
+            F::from_usize(i + 1).unwrap() / F::from_usize(pieces).unwrap(),
+        );
+        ops.append(&mut _line(a.x, a.y, b.x, b.y, o, i == 0, overlay));
+    }
+    ops
+}
+
+/// Replaces each curve in `ops` with short line segments along it, each point pushed
+/// sideways by a small random amount. The end point of every curve is kept.
+fn _pencil_texture<F: Float + Trig + FromPrimitive>(
+    ops: Vec<Op<F>>,
+    o: &mut Options,
+) -> Vec<Op<F>> {
+    let step = _c::<F>(3.0);
+    let amplitude = _c::<F>(0.25 * o.roughness.unwrap_or(1.0));
+    let mut textured = vec![];
+    let mut current = Point2D::new(F::zero(), F::zero());
+    for op in ops {
+        if op.op != OpType::BCurveTo {
+            if let [.., x, y] = op.data[..] {
+                current = Point2D::new(x, y);
+            }
+            textured.push(op);
+            continue;
+        }
+        let d = &op.data;
+        let (p0, p1, p2, p3) = (
+            current,
+            Point2D::new(d[0], d[1]),
+            Point2D::new(d[2], d[3]),
+            Point2D::new(d[4], d[5]),
+        );
+        let chord = p3 - p0;
+        let length = chord.length();
+        let normal: Vector2D<F> = if length > F::zero() {
+            Vector2D::new(-chord.y, chord.x) / length
+        } else {
+            Vector2D::zero()
+        };
+        let count = (length / step).ceil().to_usize().unwrap_or(1).max(2);
+        for i in 1..count {
+            let t = F::from_usize(i).unwrap() / F::from_usize(count).unwrap();
+            let u = F::one() - t;
+            let on_curve = p0.to_vector() * (u * u * u)
+                + p1.to_vector() * (_c::<F>(3.0) * u * u * t)
+                + p2.to_vector() * (_c::<F>(3.0) * u * t * t)
+                + p3.to_vector() * (t * t * t);
+            let wobble = amplitude * (_cc::<F>(o.random()) * _c(2.0) - F::one());
+            let p = on_curve + normal * wobble;
+            textured.push(Op { op: OpType::LineTo, data: vec![p.x, p.y] });
+        }
+        textured.push(Op { op: OpType::LineTo, data: vec![p3.x, p3.y] });
+        current = p3;
+    }
+    textured
+}
+
+/// Roughens `points` as one continuous stroke, cutting each corner short and bridging it
+/// with a curve through the corner instead of turning sharply. The corner curves take up
+/// to a quarter of the shorter adjacent segment.
+pub(crate) fn _rounded_polyline<F: Float + Trig + FromPrimitive>(
+    points: &[Point2D<F>],
+    o: &mut Options,
+) -> Vec<Op<F>> {
+    let mut ops = vec![];
+    if points.len() < 2 {
+        return ops;
+    }
+    let quarter = _c::<F>(0.25);
+    let two_thirds = _c::<F>(2.0 / 3.0);
+    let mut start = points[0];
+    for i in 1..points.len() {
+        let corner = points[i];
+        if i + 1 == points.len() {
+            ops.append(&mut _line(
+                start.x,
+                start.y,
+                corner.x,
+                corner.y,
+                o,
+                i == 1,
+                false,
+            ));
+            break;
+        }
+        let next = points[i + 1];
+        let radius = F::min((corner - points[i - 1]).length(), (next - corner).length()) * quarter;
+        let enter = corner + (points[i - 1] - corner).normalize() * radius;
+        let exit = corner + (next - corner).normalize() * radius;
+        ops.append(&mut _line(
+            start.x,
+            start.y,
+            enter.x,
+            enter.y,
+            o,
+            i == 1,
+            false,
+        ));
+        let cp1 = enter.lerp(corner, two_thirds);
+        let cp2 = exit.lerp(corner, two_thirds);
+        ops.push(Op {
+            op: OpType::BCurveTo,
+            data: vec![cp1.x, cp1.y, cp2.x, cp2.y, exit.x, exit.y],
+        });
+        start = exit;
+    }
+    ops
+}
+
+/// Moves the points of a second stroke pass toward the matching points of the first pass
+/// by `stroke_correlation`. Passes with a different op layout are left untouched.
+fn _correlate_second_pass<F: Float + Trig + FromPrimitive>(
+    first: &[Op<F>],
+    second: &mut [Op<F>],
+    o: &Options,
+) {
+    let correlation = match o.stroke_correlation {
+        Some(c) if c > 0.0 => _c::<F>(c.min(1.0)),
+        _ => return,
+    };
+    let same_layout = first.len() == second.len()
+        && first
+            .iter()
+            .zip(second.iter())
+            .all(|(a, b)| a.op == b.op && a.data.len() == b.data.len());
+    if !same_layout {
+        return;
+    }
+    for (a, b) in first.iter().zip(second.iter_mut()) {
+        for (p, q) in a.data.iter().zip(b.data.iter_mut()) {
+            *q = *q + (*p - *q) * correlation;
+        }
+    }
+}
+
 pub(crate) fn _curve<F: Float + Trig + FromPrimitive>(
     points: &[Point2D<F>],
     close_point: Option<Point2D<F>>,
@@ -807,6 +1167,15 @@ fn _curve_with_offset<F: Float + Trig + FromPrimitive>(
     offset: F,
     o: &mut Options,
 ) -> Vec<Op<F>> {
+    let ps = _curve_points_with_offset(points, offset, o);
+    _curve(&ps, None, o)
+}
+
+fn _curve_points_with_offset<F: Float + Trig + FromPrimitive>(
+    points: &[Point2D<F>],
+    offset: F,
+    o: &mut Options,
+) -> Vec<Point2D<F>> {
     let mut ps: Vec<Point2D<F>> = vec![
         Point2D::new(
             points[0].x + _offset_opt(offset, o, None),
@@ -831,7 +1200,35 @@ fn _curve_with_offset<F: Float + Trig + FromPrimitive>(
         }
         i += 1;
     }
-    _curve(&ps, None, o)
+    ps
+}
+
+/// Shifts every point of a flattened curve along its normal by `distance` and
+/// rejoins the shifted points into a curve. Normals are estimated from neighbouring
+/// points so the result runs parallel to the original.
+pub(crate) fn _curve_parallel_offset<F: Float + Trig + FromPrimitive>(
+    points: &[Point2D<F>],
+    distance: F,
+    o: &mut Options,
+) -> Vec<Op<F>> {
+    let len = points.len();
+    let shifted: Vec<Point2D<F>> = points
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let prev = points[i.saturating_sub(1)];
+            let next = points[(i + 1).min(len - 1)];
+            let dx = next.x - prev.x;
+            let dy = next.y - prev.y;
+            let length = (dx * dx + dy * dy).sqrt();
+            if length == _c(0.0) {
+                *p
+            } else {
+                Point2D::new(p.x - dy / length * distance, p.y + dx / length * distance)
+            }
+        })
+        .collect();
+    _curve(&shifted, None, o)
 }
 
 pub(crate) fn _compute_ellipse_points<F: Float + Trig + FromPrimitive>(
@@ -1052,11 +1449,16 @@ fn _bezier_to<F: Float + Trig + FromPrimitive>(
     ops
 }
 
-pub fn pattern_fill_polygons<F, P>(polygon_list: P, o: &mut Options) -> OpSet<F>
+pub fn pattern_fill_polygons<F, P>(mut polygon_list: P, o: &mut Options) -> OpSet<F>
 where
     F: Float + Trig + FromPrimitive,
     P: BorrowMut<Vec<Vec<Point2D<F>>>>,
 {
+    if let Some(tolerance) = o.fill_simplify_tolerance {
+        for polygon in polygon_list.borrow_mut().iter_mut() {
+            *polygon = simplify_polygon(polygon, tolerance);
+        }
+    }
     let filler = if let Some(fill_style) = o.fill_style.as_ref() {
         match fill_style {
             FillStyle::Hachure => get_filler(ScanLineHachure),
@@ -1065,12 +1467,64 @@ where
             FillStyle::CrossHatch => get_filler(HatchFiller),
             FillStyle::ZigZag => get_filler(ZigZagFiller),
             FillStyle::ZigZagLine => get_filler(ZigZagLineFiller),
+            FillStyle::Concentric => get_filler(ConcentricFiller),
             _ => get_filler(ScanLineHachure),
         }
     } else {
         get_filler(ScanLineHachure)
     };
-    filler.fill_polygons(polygon_list, o)
+    let stroke_roughness = o.roughness;
+    if o.clean_fill.unwrap_or(false) {
+        o.roughness = Some(0.0);
+    } else if let Some(fill_roughness) = o.fill_roughness {
+        o.roughness = Some(fill_roughness);
+    }
+    let hachure_angle = o.hachure_angle;
+    if o.auto_hachure_angle.unwrap_or(false) {
+        let vertices: Vec<Point2D<F>> = polygon_list.borrow().iter().flatten().copied().collect();
+        if let Some(axis) = principal_axis_angle(&vertices) {
+            // hachure lines run at `hachure_angle + 90` degrees, across the axis
+            o.hachure_angle = axis.to_f32().map(|a| -a);
+        }
+    }
+    let multi_stroke_fill = o.disable_multi_stroke_fill;
+    if let Some(threshold) = o.adaptive_multi_stroke_fill {
+        let vertices = polygon_list
+            .borrow()
+            .iter()
+            .flatten()
+            .copied()
+            .collect::<Vec<_>>();
+        let size = Box2D::from_points(vertices).size();
+        if size.width < _c(threshold) && size.height < _c(threshold) {
+            o.disable_multi_stroke_fill = Some(true);
+        }
+    }
+    let fill = filler.fill_polygons(polygon_list, o);
+    o.roughness = stroke_roughness;
+    o.hachure_angle = hachure_angle;
+    o.disable_multi_stroke_fill = multi_stroke_fill;
+    fill
+}
+
+/// Reduces the vertices of `polygon` with Ramer-Douglas-Peucker. Polygons that would
+/// collapse below three vertices are kept as they are.
+fn simplify_polygon<F: Float + FromPrimitive>(
+    polygon: &[Point2D<F>],
+    tolerance: f32,
+) -> Vec<Point2D<F>> {
+    let points: Vec<Point2D<f64>> = polygon
+        .iter()
+        .map(|p| Point2D::new(p.x.to_f64().unwrap(), p.y.to_f64().unwrap()))
+        .collect();
+    let simplified = simplify(&points, tolerance as f64);
+    if simplified.len() < 3 {
+        return polygon.to_vec();
+    }
+    simplified
+        .iter()
+        .map(|p| Point2D::new(_cc::<F>(p.x), _cc::<F>(p.y)))
+        .collect()
 }
 
 pub fn pattern_fill_arc<F>(
@@ -1132,11 +1586,24 @@ where
     let mut ops = vec![];
     let mut first = Point2D::new(_c::<F>(0.0), _c::<F>(0.0));
     let mut current = Point2D::new(_c::<F>(0.0), _c::<F>(0.0));
-    let path_parser = PathParser::from(path.as_ref());
-    let path_segments: Vec<PathSegment> = path_parser.flatten().collect();
-    let normalized_segments = normalize(absolutize(path_segments.iter()));
+    let normalized_segments = to_canonical(&path);
 
-    opset_from_path(o, ops, first, current, normalized_segments)
+    opset_from_path(o, ops, first, current, normalized_segments.into_iter())
+}
+
+/// Same as [`svg_path`], preceded by the fill of the path's subpaths when `fill` is set.
+pub fn svg_path_with_fill<F>(path: String, o: &mut Options) -> Vec<OpSet<F>>
+where
+    F: Float + FromPrimitive + Trig + MulAssign + Display,
+{
+    let mut sets = vec![];
+    if o.fill.is_some() {
+        let distance = (F::one() + _c::<F>(o.roughness.unwrap_or(1.0))) / _c::<F>(2.0);
+        let subpaths = points_on_path(path.clone(), Some(F::one()), Some(distance));
+        sets.extend(path_fills(&subpaths, o));
+    }
+    sets.push(svg_path(path, o));
+    sets
 }
 
 pub fn svg_segments<F>(path_segments: Vec<PathSegment>, o: &mut Options) -> OpSet<F>
@@ -1151,6 +1618,102 @@ where
     opset_from_path(o, ops, first, current, normalized_segments)
 }
 
+/// Fill op sets for the subpaths of a path, either one even-odd group or one per subpath
+/// depending on `fill_subpaths_separately`. Open subpaths are left out unless
+/// `fill_open_path_close` is set.
+pub(crate) fn path_fills<F>(subpaths: &[Vec<Point2D<F>>], o: &mut Options) -> Vec<OpSet<F>>
+where
+    F: Float + Trig + FromPrimitive,
+{
+    let close_open = o.fill_open_path_close.unwrap_or(true);
+    let subpaths: Vec<Vec<Point2D<F>>> = subpaths
+        .iter()
+        .filter(|subpath| close_open || subpath.first() == subpath.last())
+        .cloned()
+        .collect();
+    if subpaths.is_empty() {
+        return vec![];
+    }
+    let groups: Vec<Vec<Vec<Point2D<F>>>> = if o.fill_subpaths_separately.unwrap_or(false) {
+        subpaths.into_iter().map(|subpath| vec![subpath]).collect()
+    } else {
+        vec![subpaths]
+    };
+    groups
+        .into_iter()
+        .map(|group| {
+            if o.fill_style == Some(FillStyle::Solid) {
+                solid_fill_polygon(&group, o)
+            } else {
+                pattern_fill_polygons(group, o)
+            }
+        })
+        .collect()
+}
+
+/// Roughness at arc length `position` of a path `total` long, following `roughness_ramp`
+/// when it is set.
+fn _ramped_roughness<F: Float + FromPrimitive>(
+    o: &Options,
+    roughness: Option<f32>,
+    position: F,
+    total: F,
+) -> Option<f32> {
+    match o.roughness_ramp {
+        Some((start, end)) => {
+            let t = if total > F::zero() {
+                (position / total).to_f32().unwrap_or(0.0)
+            } else {
+                0.0
+            };
+            Some(start + (end - start) * t)
+        }
+        None => roughness,
+    }
+}
+
+/// Approximate length of each normalized segment. Curves are measured along their
+/// control polygon.
+fn _path_segment_lengths<F: Float + FromPrimitive + Trig>(segments: &[PathSegment]) -> Vec<F> {
+    let mut first = Point2D::new(0.0, 0.0);
+    let mut current = first;
+    segments
+        .iter()
+        .map(|segment| {
+            let length = match *segment {
+                PathSegment::MoveTo { x, y, .. } => {
+                    first = Point2D::new(x, y);
+                    current = first;
+                    0.0
+                }
+                PathSegment::LineTo { x, y, .. } => {
+                    let to = Point2D::new(x, y);
+                    let length = (to - current).length();
+                    current = to;
+                    length
+                }
+                PathSegment::CurveTo { x1, y1, x2, y2, x, y, .. } => {
+                    let (c1, c2, to) = (
+                        Point2D::new(x1, y1),
+                        Point2D::new(x2, y2),
+                        Point2D::new(x, y),
+                    );
+                    let length = (c1 - current).length() + (c2 - c1).length() + (to - c2).length();
+                    current = to;
+                    length
+                }
+                PathSegment::ClosePath { .. } => {
+                    let length = (first - current).length();
+                    current = first;
+                    length
+                }
+                _ => 0.0,
+            };
+            _cc::<F>(length)
+        })
+        .collect()
+}
+
 fn opset_from_path<F>(
     o: &mut Options,
     mut ops: Vec<Op<F>>,
@@ -1161,7 +1724,14 @@ fn opset_from_path<F>(
 where
     F: Float + FromPrimitive + Trig,
 {
-    for segment in normalized_segments {
+    let segments: Vec<PathSegment> = normalized_segments.collect();
+    let lengths = _path_segment_lengths::<F>(&segments);
+    let total = lengths.iter().fold(F::zero(), |acc, l| acc + *l);
+    let roughness = o.roughness;
+    let mut travelled = F::zero();
+    for (segment, length) in segments.into_iter().zip(lengths) {
+        o.roughness = _ramped_roughness(o, roughness, travelled + length / _c(2.0), total);
+        travelled = travelled + length;
         match segment {
             PathSegment::MoveTo { abs: true, x, y } => {
                 let ro = _c::<F>(1.0) * _c::<F>(o.max_randomness_offset.unwrap_or(2.0));
@@ -1217,6 +1787,7 @@ where
             _ => panic!("Unexpected segment type"),
         }
     }
+    o.roughness = roughness;
     OpSet {
         op_set_type: OpSetType::Path,
         ops,
@@ -1233,8 +1804,15 @@ mod test {
     use plotlib::style::{PointMarker, PointStyle};
     use plotlib::view::ContinuousView;
 
-    use super::{EllipseParams, _compute_ellipse_points, _curve};
+    use super::{
+        _clamp_bowing,
+        _compute_ellipse_points,
+        _curve,
+        _curve_parallel_offset,
+        EllipseParams,
+    };
     use crate::core::{Op, OpSet, OpSetType, OpType, Options, OptionsBuilder};
+    use crate::filler::scan_line_hachure::polygon_hachure_lines;
 
     fn get_default_options() -> Options {
         OptionsBuilder::default()
@@ -1523,6 +2101,395 @@ mod test {
         );
     }
 
+    #[test]
+    fn curve_parallel_offset() {
+        let points = [
+            point2(0.0, 0.0),
+            point2(1.0, 0.0),
+            point2(2.0, 0.0),
+            point2(3.0, 0.0),
+            point2(4.0, 0.0),
+        ];
+        let original = _curve(&points, None, &mut get_default_options());
+        let shifted = _curve_parallel_offset(&points, 2.0, &mut get_default_options());
+        assert_eq!(original.len(), shifted.len());
+        for (o, s) in original.iter().zip(shifted.iter()) {
+            assert_eq!(o.op, s.op);
+            for (i, (a, b)) in o.data.iter().zip(s.data.iter()).enumerate() {
+                if i % 2 == 0 {
+                    assert_eq!(a, b);
+                } else {
+                    assert_eq!(a + 2.0, *b);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn perfect_ellipse_keeps_requested_radii() {
+        let mut o = OptionsBuilder::default()
+            .seed(345_u64)
+            .perfect_ellipse(true)
+            .build()
+            .unwrap();
+        let params = super::generate_ellipse_params(200.0f32, 100.0, &mut o);
+        assert_eq!(params.rx, 100.0);
+        assert_eq!(params.ry, 50.0);
+
+        let result = super::ellipse_with_params(0.0f32, 0.0, &mut o, &params);
+        let max_x = result
+            .estimated_points
+            .iter()
+            .map(|p| p.x)
+            .fold(f32::MIN, f32::max);
+        let max_y = result
+            .estimated_points
+            .iter()
+            .map(|p| p.y)
+            .fold(f32::MIN, f32::max);
+        let tolerance = 5.0;
+        assert!((max_x - 100.0).abs() < tolerance);
+        assert!((max_y - 50.0).abs() < tolerance);
+        assert!(result.opset.ops.len() > 2);
+    }
+
+    #[test]
+    fn multi_stroke_offset_increases_divergence() {
+        let points = vec![
+            point2(0.0f32, 0.0),
+            point2(30.0, 20.0),
+            point2(60.0, 0.0),
+            point2(90.0, 20.0),
+        ];
+        let divergence = |scale: f32| {
+            let mut o = OptionsBuilder::default()
+                .seed(345_u64)
+                .multi_stroke_offset(scale)
+                .build()
+                .unwrap();
+            let ops = super::curve(&points, &mut o).ops;
+            let (first, second) = ops.split_at(ops.len() / 2);
+            first
+                .iter()
+                .zip(second)
+                .flat_map(|(a, b)| a.data.iter().zip(&b.data).map(|(p, q)| (p - q).abs()))
+                .sum::<f32>()
+        };
+        assert!(divergence(4.0) > divergence(1.0));
+    }
+
+    #[test]
+    fn bowing_clamped_on_short_segments() {
+        let o = OptionsBuilder::default()
+            .max_bowing_ratio(0.1)
+            .build()
+            .unwrap();
+        let (x, y) = _clamp_bowing(3.0f32, 4.0, 10.0, &o);
+        assert!((x.hypot(y) - 1.0).abs() < 1e-6);
+        assert!((x / y - 0.75).abs() < 1e-6);
+        assert_eq!(_clamp_bowing(3.0f32, 4.0, 100.0, &o), (3.0, 4.0));
+        assert_eq!(
+            _clamp_bowing(3.0f32, 4.0, 10.0, &get_default_options()),
+            (3.0, 4.0)
+        );
+    }
+
+    #[test]
+    fn fill_simplify_tolerance_keeps_hachure_close() {
+        let dense: Vec<_> = (0..10_000)
+            .map(|i| {
+                let a = i as f32 / 10_000.0 * std::f32::consts::TAU;
+                point2(100.0 + 80.0 * a.cos(), 100.0 + 80.0 * a.sin())
+            })
+            .collect();
+        let simplified = super::simplify_polygon(&dense, 0.5);
+        assert!(simplified.len() < dense.len() / 10);
+
+        let options = OptionsBuilder::default()
+            .hachure_gap(10.0)
+            .hachure_angle(-90.0)
+            .build()
+            .unwrap();
+        let full = polygon_hachure_lines(&mut vec![dense], &options);
+        let reduced = polygon_hachure_lines(&mut vec![simplified], &options);
+        assert_eq!(full.len(), reduced.len());
+        let total =
+            |lines: &[crate::geometry::Line<f32>]| lines.iter().map(|l| l.length()).sum::<f32>();
+        assert!((total(&full) - total(&reduced)).abs() / total(&full) < 0.01);
+    }
+
+    #[test]
+    fn solid_fill_concave_polygon() {
+        let l_shape = vec![
+            point2(0.0f32, 0.0),
+            point2(2.0, 0.0),
+            point2(2.0, 1.0),
+            point2(1.0, 1.0),
+            point2(1.0, 2.0),
+            point2(0.0, 2.0),
+        ];
+        let mut o = get_default_options();
+        let result = super::solid_fill_polygon(&vec![l_shape.clone()], &mut o);
+        assert_eq!(result.op_set_type, OpSetType::FillPath);
+        assert_eq!(result.ops.len(), l_shape.len());
+        assert_eq!(result.ops[0].op, OpType::Move);
+        let max_offset = o.max_randomness_offset.unwrap() * o.roughness.unwrap();
+        for (op, vertex) in result.ops.iter().zip(l_shape.iter()) {
+            assert!((op.data[0] - vertex.x).abs() <= max_offset);
+            assert!((op.data[1] - vertex.y).abs() <= max_offset);
+        }
+        assert!(result.ops[1..].iter().all(|op| op.op == OpType::LineTo));
+    }
+
+    #[test]
+    fn stroke_correlation_blends_second_pass() {
+        let line = |correlation: Option<f32>| {
+            let mut o = get_default_options();
+            o.stroke_correlation = correlation;
+            super::line(0.0f32, 0.0, 100.0, 0.0, &mut o).ops
+        };
+        let independent = line(None);
+        assert_eq!(line(Some(0.0)), independent);
+        assert_ne!(independent[0].data, independent[2].data);
+
+        let correlated = line(Some(1.0));
+        assert_eq!(correlated.len(), 4);
+        for (first, second) in correlated[..2].iter().zip(correlated[2..].iter()) {
+            for (p, q) in first.data.iter().zip(second.data.iter()) {
+                assert!((p - q).abs() < 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn max_segment_length_subdivides_long_lines() {
+        let count =
+            |ops: &[Op<f32>], op_type: OpType| ops.iter().filter(|op| op.op == op_type).count();
+        let mut o = get_default_options();
+        o.max_segment_length = Some(200.0);
+        o.disable_multi_stroke = Some(true);
+        let single = super::line(0.0f32, 0.0, 1000.0, 0.0, &mut o).ops;
+        assert_eq!(count(&single, OpType::Move), 1);
+        assert_eq!(count(&single, OpType::BCurveTo), 5);
+
+        o.disable_multi_stroke = Some(false);
+        let double = super::line(0.0f32, 0.0, 1000.0, 0.0, &mut o).ops;
+        assert_eq!(count(&double, OpType::Move), 2);
+        assert_eq!(count(&double, OpType::BCurveTo), 10);
+
+        let short = super::line(0.0f32, 0.0, 150.0, 0.0, &mut o).ops;
+        assert_eq!(short.len(), 4);
+    }
+
+    #[test]
+    fn deterministic_preview_draws_exact_corners() {
+        let mut o = get_default_options();
+        o.deterministic_preview = Some(true);
+        let opset = super::rectangle(10.0f32, 20.0, 30.0, 40.0, &mut o);
+        let corners = [(10.0, 20.0), (40.0, 20.0), (40.0, 60.0), (10.0, 60.0)];
+        for (i, op) in opset.ops.iter().enumerate() {
+            // every edge is drawn twice, as a move followed by a curve
+            let edge = i / 4;
+            let (x, y) = match op.op {
+                OpType::Move => corners[edge],
+                _ => corners[(edge + 1) % 4],
+            };
+            let end = op.data.len() - 2;
+            assert_eq!((op.data[end], op.data[end + 1]), (x, y));
+        }
+        assert_eq!(opset.ops.len(), 16);
+    }
+
+    #[test]
+    fn roughness_ramp_increases_jitter_along_path() {
+        let mut o = get_default_options();
+        o.roughness_ramp = Some((0.0, 3.0));
+        o.disable_multi_stroke = Some(true);
+        let points: Vec<_> = (0..=10).map(|i| point2(i as f32 * 50.0, 0.0)).collect();
+        let ops = super::linear_path(&points, false, &mut o).ops;
+        assert_eq!(o.roughness, Some(1.0));
+
+        // every segment is a move followed by a curve along y = 0
+        let jitter: Vec<f32> = ops
+            .chunks(2)
+            .map(|segment| {
+                segment
+                    .iter()
+                    .flat_map(|op| op.data.chunks(2).map(|p| p[1].abs()))
+                    .fold(0.0, f32::max)
+            })
+            .collect();
+        assert_eq!(jitter.len(), 10);
+        assert!(jitter[0] < 0.5);
+        assert!(jitter[..3].iter().sum::<f32>() < jitter[7..].iter().sum::<f32>());
+    }
+
+    #[test]
+    fn auto_hachure_angle_crosses_short_side() {
+        let mut o = get_default_options();
+        o.roughness = Some(0.0);
+        o.auto_hachure_angle = Some(true);
+        o.hachure_gap = Some(5.0);
+        let elongated = vec![vec![
+            point2(0.0f32, 0.0),
+            point2(200.0, 0.0),
+            point2(200.0, 20.0),
+            point2(0.0, 20.0),
+        ]];
+        let ops = super::pattern_fill_polygons(elongated, &mut o).ops;
+        assert_eq!(o.hachure_angle, Some(-41.0));
+
+        assert!(ops.len() > 20);
+        for pair in ops.chunks(2) {
+            let (from, to) = (&pair[0].data, &pair[1].data);
+            let (dx, dy) = (to[4] - from[0], to[5] - from[1]);
+            // the first scan line runs along the top edge and comes out empty
+            if dx == 0.0 && dy == 0.0 {
+                continue;
+            }
+            assert!(dx.abs() < 1e-3 && dy.abs() > 1.0, "{} {}", dx, dy);
+        }
+    }
+
+    #[test]
+    fn zigzag_round_joins_turns_with_curves() {
+        let square = vec![
+            point2(0.0f32, 0.0),
+            point2(60.0, 0.0),
+            point2(60.0, 60.0),
+            point2(0.0, 60.0),
+        ];
+        let fill = |round: bool| {
+            let mut o = OptionsBuilder::default()
+                .seed(1_u64)
+                .roughness(0.0)
+                .hachure_gap(8.0)
+                .fill_style(crate::core::FillStyle::ZigZagLine)
+                .zigzag_round(round)
+                .disable_multi_stroke_fill(true)
+                .build()
+                .unwrap();
+            super::pattern_fill_polygons(vec![square.clone()], &mut o).ops
+        };
+        let moves = |ops: &[Op<f32>]| ops.iter().filter(|op| op.op == OpType::Move).count();
+        let sharp = fill(false);
+        let rounded = fill(true);
+        assert!(moves(&rounded) * 4 <= moves(&sharp));
+        assert!(rounded
+            .iter()
+            .all(|op| op.op == OpType::Move || op.op == OpType::BCurveTo));
+        assert!(rounded.len() > moves(&rounded) * 2);
+    }
+
+    #[test]
+    fn adaptive_multi_stroke_fill_single_passes_small_shapes() {
+        let square = |size: f32| {
+            vec![
+                point2(0.0f32, 0.0),
+                point2(size, 0.0),
+                point2(size, size),
+                point2(0.0, size),
+            ]
+        };
+        let fill_ops = |size: f32, threshold: Option<f32>| {
+            let mut o = get_default_options();
+            o.hachure_gap = Some(4.0);
+            o.adaptive_multi_stroke_fill = threshold;
+            super::pattern_fill_polygons(vec![square(size)], &mut o)
+                .ops
+                .len()
+        };
+
+        assert_eq!(fill_ops(20.0, Some(50.0)) * 2, fill_ops(20.0, None));
+        assert_eq!(fill_ops(100.0, Some(50.0)), fill_ops(100.0, None));
+        assert!(fill_ops(20.0, Some(50.0)) < fill_ops(100.0, Some(50.0)));
+    }
+
+    #[test]
+    fn pencil_texture_wobbles_along_the_line() {
+        let turns = |pencil: bool| {
+            let mut o = get_default_options();
+            o.disable_multi_stroke = Some(true);
+            o.pencil_texture = Some(pencil);
+            let set = super::line(0.0f32, 0.0, 120.0, 0.0, &mut o);
+            let drawable =
+                crate::core::Drawable { shape: "line".into(), options: o, sets: vec![set] };
+            let ys: Vec<f32> = drawable
+                .line_segments(0.1)
+                .iter()
+                .map(|(a, _)| a.y)
+                .collect();
+            ys.windows(3)
+                .filter(|w| (w[1] - w[0]) * (w[2] - w[1]) < 0.0)
+                .count()
+        };
+
+        assert!(turns(false) <= 1);
+        assert!(turns(true) > 10);
+    }
+
+    #[test]
+    fn svg_path_with_fill_strokes_and_fills_closed_paths() {
+        let heart = "M140 20C73 20 20 74 20 140c0 135 136 170 228 303 88-132 229-173 229-303 \
+                     0-66-54-120-120-120-48 0-90 28-109 69-19-41-60-69-108-69z";
+        let mut o = get_default_options();
+        o.fill = Some(crate::Srgba::new(1.0, 0.0, 0.0, 1.0));
+        let sets = super::svg_path_with_fill::<f32>(heart.into(), &mut o);
+        let types: Vec<OpSetType> = sets.iter().map(|set| set.op_set_type.clone()).collect();
+        assert_eq!(types, vec![OpSetType::FillSketch, OpSetType::Path]);
+
+        o.fill_open_path_close = Some(false);
+        let open = super::svg_path_with_fill::<f32>("M0 0L50 0L50 50".into(), &mut o);
+        assert_eq!(open.len(), 1);
+        let closed = super::svg_path_with_fill::<f32>("M0 0L50 0L50 50Z".into(), &mut o);
+        assert_eq!(closed.len(), 2);
+    }
+
+    #[test]
+    fn ellipse_inner_pass_differs_between_shapes() {
+        let params = EllipseParams { increment: 0.5f32, rx: 30.0, ry: 20.0 };
+        let mut o = get_default_options();
+        let a = super::ellipse_with_params(50.0, 50.0, &mut o, &params)
+            .opset
+            .ops;
+        let b = super::ellipse_with_params(50.0, 50.0, &mut o, &params)
+            .opset
+            .ops;
+
+        let inner_start =
+            |ops: &[Op<f32>]| ops.iter().rposition(|op| op.op == OpType::Move).unwrap();
+        let (ia, ib) = (inner_start(&a), inner_start(&b));
+        assert!(ia > 0);
+        assert_ne!(a[ia..], b[ib..]);
+    }
+
+    #[test]
+    fn seeded_filled_ellipse_is_reproducible() {
+        let options = OptionsBuilder::default()
+            .seed(21_u64)
+            .fill(crate::Srgba::new(1.0, 0.0, 0.0, 1.0))
+            .build()
+            .unwrap();
+        let generator = crate::generator::Generator::default();
+        let first = generator.ellipse(50.0f32, 50.0, 60.0, 40.0, &Some(options.clone()));
+        let again = generator.ellipse(50.0f32, 50.0, 60.0, 40.0, &Some(options));
+
+        let outline = first
+            .sets
+            .iter()
+            .find(|set| set.op_set_type == OpSetType::Path)
+            .unwrap();
+        assert_eq!(
+            outline
+                .ops
+                .iter()
+                .filter(|op| op.op == OpType::Move)
+                .count(),
+            2
+        );
+        assert_eq!(first.sets, again.sets);
+    }
+
     #[test]
     #[ignore = "utility to see results quickly"]
     fn plot_points() {
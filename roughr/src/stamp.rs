@@ -0,0 +1,95 @@
+use euclid::default::{Point2D, Vector2D};
+use euclid::Trig;
+use num_traits::{Float, FromPrimitive};
+
+use crate::core::Drawable;
+use crate::generator::Generator;
+
+type StampFn<F> = dyn Fn(&mut Generator, Point2D<F>) -> Drawable<F>;
+
+/// A reusable shape that can be drawn at many positions. The wrapped closure receives
+/// the generator and the position to draw at, so it decides whether every copy gets
+/// fresh jitter or identical jitter.
+pub struct Stamp<F: Float + Trig> {
+    draw: Box<StampFn<F>>,
+}
+
+impl<F: Float + Trig + FromPrimitive> Stamp<F> {
+    pub fn new(draw: impl Fn(&mut Generator, Point2D<F>) -> Drawable<F> + 'static) -> Self {
+        Stamp { draw: Box::new(draw) }
+    }
+
+    pub fn stamp(&self, gen: &mut Generator, at: Point2D<F>) -> Drawable<F> {
+        (self.draw)(gen, at)
+    }
+
+    /// Stamps `columns` x `rows` copies starting at `origin`, row by row.
+    pub fn stamp_grid(
+        &self,
+        gen: &mut Generator,
+        origin: Point2D<F>,
+        columns: usize,
+        rows: usize,
+        spacing: Vector2D<F>,
+    ) -> Vec<Drawable<F>> {
+        let mut drawables = Vec::with_capacity(columns * rows);
+        for row in 0..rows {
+            for column in 0..columns {
+                let offset = Vector2D::new(
+                    spacing.x * F::from_usize(column).unwrap(),
+                    spacing.y * F::from_usize(row).unwrap(),
+                );
+                drawables.push(self.stamp(gen, origin + offset));
+            }
+        }
+        drawables
+    }
+
+    /// Stamps copies every `spacing` units along the polyline through `points`, starting
+    /// at its first point.
+    pub fn stamp_along_path(
+        &self,
+        gen: &mut Generator,
+        points: &[Point2D<F>],
+        spacing: F,
+    ) -> Vec<Drawable<F>> {
+        let mut drawables = vec![];
+        if points.is_empty() || spacing <= F::zero() {
+            return drawables;
+        }
+        drawables.push(self.stamp(gen, points[0]));
+        let mut next = spacing;
+        let mut travelled = F::zero();
+        for segment in points.windows(2) {
+            let length = (segment[1] - segment[0]).length();
+            while next <= travelled + length {
+                let t = (next - travelled) / length;
+                drawables.push(self.stamp(gen, segment[0].lerp(segment[1], t)));
+                next = next + spacing;
+            }
+            travelled = travelled + length;
+        }
+        drawables
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use euclid::default::Vector2D;
+    use euclid::point2;
+
+    use super::Stamp;
+
+    #[test]
+    fn stamp_grid_draws_every_cell() {
+        let stamp = Stamp::new(|gen, at| gen.circle(at.x, at.y, 10.0f32, &None));
+        let mut gen = Default::default();
+        let drawables =
+            stamp.stamp_grid(&mut gen, point2(0.0, 0.0), 3, 3, Vector2D::new(20.0, 20.0));
+
+        assert_eq!(drawables.len(), 9);
+        assert!(drawables.iter().all(|d| d.shape == "circle"));
+        let last = drawables[8].bounds().unwrap().center();
+        assert!((last.x - 40.0).abs() < 5.0 && (last.y - 40.0).abs() < 5.0);
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+
+use palette::Srgba;
+use svgtypes::{Length, Paint};
+
+const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+
+/// An element found in an svg document.
+pub(crate) struct SvgElement {
+    pub(crate) name: String,
+    pub(crate) attributes: HashMap<String, String>,
+}
+
+impl SvgElement {
+    pub(crate) fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.get(name).map(|v| v.as_str())
+    }
+}
+
+/// Parses `source` and returns its svg elements in document order. Elements are matched by
+/// local name, so `svg:rect` is a `rect`, and elements of other namespaces are skipped.
+/// Declarations of an inline `style` attribute override presentation attributes of the same
+/// name. Returns no elements when `source` is not well formed XML.
+pub(crate) fn svg_elements(source: &str) -> Vec<SvgElement> {
+    let document = match roxmltree::Document::parse(source) {
+        Ok(document) => document,
+        Err(_) => return vec![],
+    };
+    document
+        .descendants()
+        .filter(|node| node.is_element())
+        .filter(|node| matches!(node.tag_name().namespace(), None | Some(SVG_NAMESPACE)))
+        .map(|node| {
+            let mut attributes: HashMap<String, String> = node
+                .attributes()
+                .filter(|attribute| attribute.namespace().is_none())
+                .map(|attribute| (attribute.name().to_string(), attribute.value().to_string()))
+                .collect();
+            if let Some(style) = node.attribute("style") {
+                attributes.extend(parse_style(style));
+            }
+            SvgElement {
+                name: node.tag_name().name().to_string(),
+                attributes,
+            }
+        })
+        .collect()
+}
+
+/// Splits an inline css declaration list such as `fill: red; stroke-width: 2` into pairs.
+fn parse_style(style: &str) -> Vec<(String, String)> {
+    style
+        .split(';')
+        .filter_map(|declaration| {
+            let (name, value) = declaration.split_once(':')?;
+            let (name, value) = (name.trim(), value.trim());
+            if name.is_empty() || value.is_empty() {
+                return None;
+            }
+            Some((name.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Parses an svg paint attribute. Returns `None` for values that should leave the
+/// default untouched, such as `inherit` or paint servers.
+pub(crate) fn svg_paint(value: &str) -> Option<Option<Srgba>> {
+    match Paint::from_str(value).ok()? {
+        Paint::None => Some(None),
+        Paint::Color(c) => Some(Some(Srgba::new(
+            c.red as f32 / 255.0,
+            c.green as f32 / 255.0,
+            c.blue as f32 / 255.0,
+            c.alpha as f32 / 255.0,
+        ))),
+        _ => None,
+    }
+}
+
+/// Parses an svg length attribute as a number in user units.
+pub(crate) fn svg_number(value: &str) -> Option<f64> {
+    value.parse::<Length>().ok().map(|l| l.number)
+}
+
+#[cfg(test)]
+mod test {
+    use super::svg_elements;
+
+    #[test]
+    fn entities_are_decoded() {
+        let elements = svg_elements(r#"<svg><path d="M0 0 &#76; 10 10" id="a&amp;b"/></svg>"#);
+        assert_eq!(elements[1].attribute("d"), Some("M0 0 L 10 10"));
+        assert_eq!(elements[1].attribute("id"), Some("a&b"));
+    }
+
+    #[test]
+    fn comments_and_cdata_do_not_end_tags() {
+        let elements = svg_elements(
+            r#"<svg><!-- <rect width="1"/> -> --><style><![CDATA[ a > b {} ]]></style><circle r="2"/></svg>"#,
+        );
+        let names: Vec<&str> = elements.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["svg", "style", "circle"]);
+        assert_eq!(elements[2].attribute("r"), Some("2"));
+    }
+
+    #[test]
+    fn prefixed_svg_elements_are_found() {
+        let elements = svg_elements(
+            r#"<svg:svg xmlns:svg="http://www.w3.org/2000/svg" xmlns:x="urn:x"><svg:rect width="4"/><x:rect/></svg:svg>"#,
+        );
+        let names: Vec<&str> = elements.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["svg", "rect"]);
+        assert_eq!(elements[1].attribute("width"), Some("4"));
+    }
+
+    #[test]
+    fn style_overrides_presentation_attributes() {
+        let elements =
+            svg_elements(r#"<rect fill="red" stroke="blue" style="fill: green;stroke-width:3"/>"#);
+        assert_eq!(elements[0].attribute("fill"), Some("green"));
+        assert_eq!(elements[0].attribute("stroke"), Some("blue"));
+        assert_eq!(elements[0].attribute("stroke-width"), Some("3"));
+    }
+}
@@ -327,6 +327,77 @@ pub fn print_line_segment(segment: &PathSegment) {
     }
 }
 
+/// Joins consecutive subpaths whose boundaries touch. When a subpath starts within `tolerance` of the point
+/// where the previous one ended, the `Z`/`M` boundary between them is replaced with an `L` (or dropped when the
+/// points coincide exactly). Segments are absolutized first, so the result only contains absolute commands.
+pub fn join_subpaths(
+    path_segments: impl Iterator<Item = impl Borrow<PathSegment>>,
+    tolerance: f64,
+) -> Vec<PathSegment> {
+    let mut result = vec![];
+    let (mut cx, mut cy, mut subx, mut suby) = (0.0, 0.0, 0.0, 0.0);
+    let mut pending_close = false;
+    for segment in absolutize(path_segments) {
+        match segment {
+            PathSegment::MoveTo { x, y, .. } => {
+                let joinable = !result.is_empty() && (x - cx).hypot(y - cy) <= tolerance;
+                if pending_close {
+                    pending_close = false;
+                    if joinable {
+                        result.push(PathSegment::LineTo { abs: true, x: subx, y: suby });
+                        continue;
+                    }
+                    result.push(PathSegment::ClosePath { abs: true });
+                }
+                if joinable {
+                    if x != cx || y != cy {
+                        result.push(PathSegment::LineTo { abs: true, x, y });
+                    }
+                } else {
+                    result.push(segment);
+                    subx = x;
+                    suby = y;
+                }
+                cx = x;
+                cy = y;
+            }
+            PathSegment::ClosePath { .. } => {
+                if pending_close {
+                    result.push(PathSegment::ClosePath { abs: true });
+                }
+                pending_close = true;
+                cx = subx;
+                cy = suby;
+            }
+            _ => {
+                if pending_close {
+                    result.push(PathSegment::ClosePath { abs: true });
+                    pending_close = false;
+                }
+                match segment {
+                    PathSegment::HorizontalLineTo { x, .. } => cx = x,
+                    PathSegment::VerticalLineTo { y, .. } => cy = y,
+                    PathSegment::LineTo { x, y, .. }
+                    | PathSegment::CurveTo { x, y, .. }
+                    | PathSegment::SmoothCurveTo { x, y, .. }
+                    | PathSegment::Quadratic { x, y, .. }
+                    | PathSegment::SmoothQuadratic { x, y, .. }
+                    | PathSegment::EllipticalArc { x, y, .. } => {
+                        cx = x;
+                        cy = y;
+                    }
+                    _ => {}
+                }
+                result.push(segment);
+            }
+        }
+    }
+    if pending_close {
+        result.push(PathSegment::ClosePath { abs: true });
+    }
+    result
+}
+
 /// Normalize takes a list of absolute segments and outputs a list of segments with only four commands: M, L, C, Z. So every segment is described as move, line, or a bezier curve (cubic).
 /// This is useful when translating SVG paths to non SVG mediums - Canvas, or some other graphics platform. Most such platforms will support lines and bezier curves.
 /// It also simplifies the cases to consider when modifying these segments.
@@ -521,6 +592,13 @@ pub fn normalize(
     out.into_iter()
 }
 
+/// Parses `path` and returns it as absolute `M`, `L`, `C` and `Z` segments, the same as
+/// `normalize(absolutize(..))` over the parsed segments. Segments that fail to parse are skipped.
+pub fn to_canonical(path: &str) -> Vec<PathSegment> {
+    let path_segments: Vec<PathSegment> = svgtypes::PathParser::from(path).flatten().collect();
+    normalize(absolutize(path_segments.iter())).collect()
+}
+
 fn rotate(x: f64, y: f64, angle_rad: f64) -> (f64, f64) {
     let rotated_x = x * angle_rad.cos() - y * angle_rad.sin();
     let rotated_y = x * angle_rad.sin() + y * angle_rad.cos();
@@ -1225,4 +1303,39 @@ mod test {
             ]
         );
     }
+
+    #[test]
+    pub fn join_subpaths_touching_open_paths() {
+        let path_parser = PathParser::from("M 0 0 L 10 0 M 10.0001 0 L 10 10 M 50 50 L 60 60");
+        let path_segments: Vec<PathSegment> = path_parser.flatten().collect();
+        let joined = super::join_subpaths(path_segments.iter(), 0.001);
+        assert_eq!(
+            joined,
+            vec![
+                PathSegment::MoveTo { abs: true, x: 0.0, y: 0.0 },
+                PathSegment::LineTo { abs: true, x: 10.0, y: 0.0 },
+                PathSegment::LineTo { abs: true, x: 10.0001, y: 0.0 },
+                PathSegment::LineTo { abs: true, x: 10.0, y: 10.0 },
+                PathSegment::MoveTo { abs: true, x: 50.0, y: 50.0 },
+                PathSegment::LineTo { abs: true, x: 60.0, y: 60.0 },
+            ]
+        );
+    }
+
+    #[test]
+    pub fn to_canonical_matches_manual_pipeline() {
+        let path = "m 10 10 h 20 v 20 q 5 5 -10 10 a 5 5 0 0 1 -10 -10 z";
+        let path_segments: Vec<PathSegment> = PathParser::from(path).flatten().collect();
+        let manual: Vec<PathSegment> = super::normalize(absolutize(path_segments.iter())).collect();
+
+        let canonical = super::to_canonical(path);
+        assert_eq!(canonical, manual);
+        assert!(canonical.iter().all(|segment| matches!(
+            segment,
+            PathSegment::MoveTo { abs: true, .. }
+                | PathSegment::LineTo { abs: true, .. }
+                | PathSegment::CurveTo { abs: true, .. }
+                | PathSegment::ClosePath { .. }
+        )));
+    }
 }
@@ -1,12 +1,64 @@
 use std::collections::{HashMap, VecDeque};
+use std::fmt;
 
 use cgmath::num_traits::Pow;
 use cgmath::{Angle, Deg, Matrix3, Rad, Vector2, Vector3};
+use euclid::default::Point2D;
+use points_on_curve::points_on_bezier_curves;
 use svgtypes::{PathParser, PathSegment, TransformListParser, TransformListToken};
 
 use super::ellipse::Ellipse;
 use crate::a2c::a2c;
 
+/// Error returned when a transform string can not be applied to a path.
+#[derive(Debug)]
+pub enum TransformError {
+    /// Transform list could not be parsed. Wraps the underlying svgtypes error.
+    Parse(svgtypes::Error),
+}
+
+impl fmt::Display for TransformError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransformError::Parse(e) => write!(f, "can not parse transform string: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for TransformError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TransformError::Parse(e) => Some(e),
+        }
+    }
+}
+
+/// Error returned when a path string contains a segment that can not be parsed.
+#[derive(Debug)]
+pub struct ParseError {
+    /// Index of the first segment that failed to parse.
+    pub segment: usize,
+    /// Underlying svgtypes error, carries the character position of the failure.
+    pub error: svgtypes::Error,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "can not parse path segment {}: {}",
+            self.segment, self.error
+        )
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+#[derive(Clone)]
 pub struct PathTransformer {
     path_segments: VecDeque<PathSegment>,
     stack: Vec<Matrix3<f64>>,
@@ -28,6 +80,17 @@ impl PathTransformer {
         }
     }
 
+    /// Strict variant of [`PathTransformer::new`]. Fails on the first segment
+    /// that can not be parsed instead of silently dropping it.
+    pub fn try_new(path: &str) -> Result<Self, ParseError> {
+        let path_segments = PathParser::from(path)
+            .enumerate()
+            .map(|(segment, ps)| ps.map_err(|error| ParseError { segment, error }))
+            .collect::<Result<VecDeque<_>, _>>()?;
+
+        Ok(PathTransformer { path_segments, stack: Vec::new() })
+    }
+
     pub fn translate(&mut self, tx: f64, ty: f64) -> &mut Self {
         self.stack
             .push(Matrix3::from_translation(Vector2::new(tx, ty)));
@@ -76,20 +139,26 @@ impl PathTransformer {
         self
     }
 
+    /// Applies an svg transform list such as `"rotate(45) translate(10,10)"`.
+    /// Panics if the transform string can not be parsed, use
+    /// [`PathTransformer::try_transform`] to handle malformed input.
     pub fn transform(&mut self, transform: String) -> &mut Self {
-        let parser = TransformListParser::from(transform.as_str());
-        let transforms = parser.into_iter().collect::<Vec<_>>();
+        self.try_transform(&transform)
+            .expect("Can not parse transform string.")
+    }
+
+    /// Applies an svg transform list, returning an error instead of panicking
+    /// when the string can not be parsed. Path is left untouched on error.
+    pub fn try_transform(&mut self, transform: &str) -> Result<&mut Self, TransformError> {
+        let parser = TransformListParser::from(transform);
+        let transforms = parser
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(TransformError::Parse)?;
         for path_transform in transforms.iter().rev() {
-            match path_transform {
-                Ok(pt) => {
-                    self.apply_token(*pt);
-                }
-                Err(_) => {
-                    panic!("Can not parse transform string.");
-                }
-            }
+            self.apply_token(*path_transform);
         }
-        self
+        Ok(self)
     }
 
     fn apply_token(&mut self, token: TransformListToken) -> &mut Self {
@@ -963,6 +1032,84 @@ impl PathTransformer {
         self
     }
 
+    /// Flattens the transformed path into polygon rings, one ring per subpath.
+    /// Curves and arcs are sampled with given `tolerance` using points_on_curve,
+    /// which makes the result usable for hit testing or as roughr fill polygons.
+    pub fn to_points(&self, tolerance: f64) -> Vec<Vec<Point2D<f64>>> {
+        let mut transformer = self.clone();
+        transformer.evaluate_stack();
+        transformer.abs().unarc().unshort();
+
+        let mut rings: Vec<Vec<Point2D<f64>>> = vec![];
+        let mut ring: Vec<Point2D<f64>> = vec![];
+        let mut current = Point2D::new(0.0, 0.0);
+        let mut contour_start = Point2D::new(0.0, 0.0);
+        for segment in transformer.path_segments.iter() {
+            match *segment {
+                PathSegment::MoveTo { x, y, .. } => {
+                    if ring.len() > 1 {
+                        rings.push(ring);
+                    }
+                    current = Point2D::new(x, y);
+                    contour_start = current;
+                    ring = vec![current];
+                }
+                PathSegment::LineTo { x, y, .. } => {
+                    current = Point2D::new(x, y);
+                    ring.push(current);
+                }
+                PathSegment::HorizontalLineTo { x, .. } => {
+                    current = Point2D::new(x, current.y);
+                    ring.push(current);
+                }
+                PathSegment::VerticalLineTo { y, .. } => {
+                    current = Point2D::new(current.x, y);
+                    ring.push(current);
+                }
+                PathSegment::CurveTo { x1, y1, x2, y2, x, y, .. } => {
+                    let end = Point2D::new(x, y);
+                    let curve = [current, Point2D::new(x1, y1), Point2D::new(x2, y2), end];
+                    ring.extend(
+                        points_on_bezier_curves(&curve, tolerance, None)
+                            .into_iter()
+                            .skip(1),
+                    );
+                    current = end;
+                }
+                PathSegment::Quadratic { x1, y1, x, y, .. } => {
+                    let end = Point2D::new(x, y);
+                    let cp = Point2D::new(x1, y1);
+                    let curve = [
+                        current,
+                        current + (cp - current) * (2.0 / 3.0),
+                        end + (cp - end) * (2.0 / 3.0),
+                        end,
+                    ];
+                    ring.extend(
+                        points_on_bezier_curves(&curve, tolerance, None)
+                            .into_iter()
+                            .skip(1),
+                    );
+                    current = end;
+                }
+                PathSegment::ClosePath { .. } => {
+                    if ring.len() > 1 {
+                        rings.push(ring);
+                    }
+                    ring = vec![contour_start];
+                    current = contour_start;
+                }
+                // arcs and shorthand segments are already resolved by unarc and unshort
+                _ => {}
+            }
+        }
+        if ring.len() > 1 {
+            rings.push(ring);
+        }
+
+        rings
+    }
+
     pub fn unshort(&mut self) -> &mut Self {
         // var segments = this.segments;
         let mut prev_control_x = 0.0;
@@ -1070,6 +1217,8 @@ impl PathTransformer {
 
 #[cfg(test)]
 mod test {
+    use euclid::point2;
+
     use super::PathTransformer;
 
     #[test]
@@ -1088,6 +1237,31 @@ mod test {
         assert_eq!(actual, "M 10 10 M 10 100 M 100 100 M 100 10 Z");
     }
 
+    #[test]
+    fn try_new_reports_malformed_segment() {
+        let result = PathTransformer::try_new("M 0 0 L 10 10 L 20 x");
+        let error = result.err().expect("malformed path should fail");
+        assert_eq!(error.segment, 2);
+
+        assert!(PathTransformer::try_new("M 0 0 L 10 10 L 20 10").is_ok());
+    }
+
+    #[test]
+    fn to_points_transformed_square() {
+        let rings = PathTransformer::new("M 0 0 L 10 0 L 10 10 L 0 10 Z".into())
+            .translate(5.0, 5.0)
+            .to_points(0.1);
+        assert_eq!(
+            rings,
+            vec![vec![
+                point2(5.0, 5.0),
+                point2(15.0, 5.0),
+                point2(15.0, 15.0),
+                point2(5.0, 15.0),
+            ]]
+        );
+    }
+
     #[test]
     #[ignore = "First M fails"]
     fn not_collapse_multiple_rel_m() {
@@ -1722,6 +1896,14 @@ mod test {
                     .to_string();
             }
 
+            #[test]
+            pub fn wrong_param_count_try_transform() {
+                let mut transformer = PathTransformer::new("M0 0 L 10 10 20 10".into());
+                let result = transformer.try_transform("rotate(10,0) scale(10,10,1)");
+                assert!(result.is_err());
+                assert_eq!(transformer.to_string(), "M 0 0 L 10 10 L 20 10");
+            }
+
             #[test]
             pub fn segment_replacement() {
                 let actual = PathTransformer::new("M0 0 H 10 V 10 Z M 100 100 h 15 v -10".into())